@@ -12,12 +12,20 @@ export CARGO_TARGET_DIR=/ci/cache/target
 # used when pointing stm32-metapac to a CI-built one.
 export CARGO_NET_GIT_FETCH_WITH_CLI=true
 
-cargo test --manifest-path ./embassy-executor/Cargo.toml --features metadata-name
+cargo test --manifest-path ./embassy-executor/Cargo.toml --features metadata-name,join-handle,fairness-debug,metrics
+cargo test --manifest-path ./embassy-executor/Cargo.toml --test test --features trace
+cargo test --manifest-path ./embassy-executor/Cargo.toml --test std --features arch-std,executor-thread,std-multithread
+cargo test --manifest-path ./embassy-executor/Cargo.toml --test ui_cortex_m --features arch-cortex-m,executor-interrupt
 cargo test --manifest-path ./embassy-futures/Cargo.toml
 cargo test --manifest-path ./embassy-sync/Cargo.toml
 cargo test --manifest-path ./embassy-embedded-hal/Cargo.toml
 cargo test --manifest-path ./embassy-hal-internal/Cargo.toml
-cargo test --manifest-path ./embassy-time/Cargo.toml --features mock-driver,embassy-time-queue-utils/generic-queue-8
+cargo test --manifest-path ./embassy-time/Cargo.toml --features mock-driver,embassy-time-queue-utils/generic-queue-8,bytemuck,num-traits,serde,fugit
+cargo test --manifest-path ./embassy-time/Cargo.toml --lib --features mock-driver,embassy-time-queue-utils/generic-queue-8,critical-section-schedule
+cargo test --manifest-path ./embassy-time/Cargo.toml --features std
+cargo test --manifest-path ./embassy-time/Cargo.toml --lib --features tick-hz-32_768
+cargo test --manifest-path ./embassy-time/Cargo.toml --lib --features tick-hz-1_000_000_000
+cargo test --manifest-path ./embassy-time/Cargo.toml --test defmt --features defmt
 cargo test --manifest-path ./embassy-time-driver/Cargo.toml
 
 cargo test --manifest-path ./embassy-boot/Cargo.toml
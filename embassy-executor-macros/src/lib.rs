@@ -14,7 +14,7 @@ use macros::*;
 /// The following restrictions apply:
 ///
 /// * The function must be declared `async`.
-/// * The function must not use generics.
+/// * The function must not use generics, unless the concrete types are listed with `generics`.
 /// * The optional `pool_size` attribute must be 1 or greater.
 ///
 ///
@@ -37,6 +37,45 @@ use macros::*;
 ///     // Function body
 /// }
 /// ```
+///
+/// Setting the name of spawned tasks, when the `metadata-name` feature is enabled. With a
+/// `pool_size` greater than 1, the index of the pool slot is appended, e.g. `uart.0`, `uart.1`:
+///
+/// ``` rust
+/// #[embassy_executor::task(pool_size = 2, name = "uart")]
+/// async fn mytask() {
+///     // Function body
+/// }
+/// ```
+///
+/// With `slots`, the task also gets a `<name>_in_slot` function, taking the index of the pool
+/// slot to spawn into as its first argument. This fails with `SpawnError::Busy` if that slot is
+/// in use, even if other slots are free:
+///
+/// ``` rust ignore
+/// #[embassy_executor::task(pool_size = 4, slots)]
+/// async fn channel_task(channel: usize) {
+///     // Function body
+/// }
+///
+/// for channel in 0..4 {
+///     spawner.spawn(channel_task_in_slot(channel, channel).unwrap());
+/// }
+/// ```
+///
+/// A task can be generic over a single type parameter if all the types it is spawned with are
+/// listed with `generics`. Every listed type gets its own pool of `pool_size` tasks. The types must
+/// be paths, use a type alias for types with generic arguments:
+///
+/// ``` rust ignore
+/// #[embassy_executor::task(generics(Uart1, Uart2))]
+/// async fn uart_task<U: Instance>(uart: U) {
+///     // Function body
+/// }
+///
+/// spawner.spawn(uart_task(p.UART1).unwrap());
+/// spawner.spawn(uart_task::<Uart2>(p.UART2).unwrap());
+/// ```
 #[proc_macro_attribute]
 pub fn task(args: TokenStream, item: TokenStream) -> TokenStream {
     task::run(args.into(), item.into()).into()
@@ -55,6 +94,8 @@ pub fn main_avr(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. As there is no process to exit, if it returns an `Err`, the
+///   main task panics with the error. The error type must implement `Debug`.
 ///
 /// ## Examples
 /// Spawning a task:
@@ -65,6 +106,23 @@ pub fn main_avr(args: TokenStream, item: TokenStream) -> TokenStream {
 ///     // Function body
 /// }
 /// ```
+///
+/// ## Interrupt mode
+///
+/// With the `interrupt` argument, the main task runs in an `InterruptExecutor` driven by that
+/// interrupt instead of in thread mode, and thread mode sleeps with `wfi`. This requires the
+/// `executor-interrupt` feature of `embassy-executor`, but not `executor-thread`.
+///
+/// The argument is the path to the interrupt number, and the interrupt handler is generated with
+/// the name of its last segment. Pick an interrupt that is otherwise unused. It keeps its reset
+/// priority, which is the highest one.
+///
+/// ``` rust,ignore
+/// #[embassy_executor::main(interrupt = "pac::Interrupt::SWI0_EGU0")]
+/// async fn main(_s: embassy_executor::Spawner) {
+///     // Function body
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn main_cortex_m(args: TokenStream, item: TokenStream) -> TokenStream {
     main::run(args.into(), item.into(), &main::ARCH_CORTEX_M).into()
@@ -80,6 +138,8 @@ pub fn main_cortex_m(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. As there is no process to exit, if it returns an `Err`, the
+///   main task panics with the error. The error type must implement `Debug`.
 ///
 /// ## Examples
 /// Spawning a task:
@@ -104,9 +164,14 @@ pub fn main_cortex_ar(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. As there is no process to exit, if it returns an `Err`, the
+///   main task panics with the error. The error type must implement `Debug`.
 ///
 /// A user-defined entry macro must provided via the `entry` argument
 ///
+/// If the entry macro takes arguments, it can also be given as raw tokens instead of a string:
+/// `entry(some_rt::entry(stack_size = 4096))`.
+///
 /// ## Examples
 /// Spawning a task:
 /// ``` rust
@@ -128,6 +193,8 @@ pub fn main_spin(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. As there is no process to exit, if it returns an `Err`, the
+///   main task panics with the error. The error type must implement `Debug`.
 ///
 /// A user-defined entry macro can be optionally provided via the `entry` argument to override the default of `riscv_rt::entry`.
 ///
@@ -153,6 +220,41 @@ pub fn main_riscv(args: TokenStream, item: TokenStream) -> TokenStream {
     main::run(args.into(), item.into(), &main::ARCH_RISCV).into()
 }
 
+/// Creates a new `executor` instance and declares an application entry point for Xtensa spawning the corresponding function body as an async task.
+///
+/// The following restrictions apply:
+///
+/// * The function must accept exactly 1 parameter, an `embassy_executor::Spawner` handle that it can use to spawn additional tasks.
+/// * The function must be declared `async`.
+/// * The function must not use generics.
+/// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. As there is no process to exit, if it returns an `Err`, the
+///   main task panics with the error. The error type must implement `Debug`.
+///
+/// A user-defined entry macro can be optionally provided via the `entry` argument to override the default of `xtensa_lx_rt::entry`.
+///
+/// ## Examples
+/// Spawning a task:
+///
+/// ``` rust
+/// #[embassy_executor::main_xtensa]
+/// async fn main(_s: embassy_executor::Spawner) {
+///     // Function body
+/// }
+/// ```
+///
+/// Spawning a task using a custom entry macro:
+/// ``` rust
+/// #[embassy_executor::main_xtensa(entry = "esp_hal::main")]
+/// async fn main(_s: embassy_executor::Spawner) {
+///     // Function body
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn main_xtensa(args: TokenStream, item: TokenStream) -> TokenStream {
+    main::run(args.into(), item.into(), &main::ARCH_XTENSA).into()
+}
+
 /// Creates a new `executor` instance and declares an application entry point for STD spawning the corresponding function body as an async task.
 ///
 /// The following restrictions apply:
@@ -161,6 +263,11 @@ pub fn main_riscv(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. If it returns an `Err`, the error is printed to stderr and the
+///   process exits with code 1. The error type must implement `Debug`.
+///
+/// By default, the executor runs on the main thread. With the `stack_size` argument, it runs on a
+/// new thread with a stack of that many bytes instead, for tasks that need a deeper stack.
 ///
 /// ## Examples
 /// Spawning a task:
@@ -171,11 +278,52 @@ pub fn main_riscv(args: TokenStream, item: TokenStream) -> TokenStream {
 ///     // Function body
 /// }
 /// ```
+///
+/// Running the executor on a thread with an 8 MiB stack:
+/// ``` rust
+/// #[embassy_executor::main(stack_size = 8388608)]
+/// async fn main(_s: embassy_executor::Spawner) {
+///     // Function body
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn main_std(args: TokenStream, item: TokenStream) -> TokenStream {
     main::run(args.into(), item.into(), &main::ARCH_STD).into()
 }
 
+/// Creates a new `executor` instance and declares an application entry point for hosted targets,
+/// such as an RTOS, where the executor's `run` returns, spawning the corresponding function body
+/// as an async task.
+///
+/// The following restrictions apply:
+///
+/// * The function must accept exactly 1 parameter, an `embassy_executor::Spawner` handle that it can use to spawn additional tasks.
+/// * The function must be declared `async`.
+/// * The function must not use generics.
+/// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. If it returns an `Err`, the error is printed to stderr and the
+///   process exits with code 1. The error type must implement `Debug`.
+///
+/// Unlike the other `main` macros, the generated `main` function returns `()` instead of `!`, and
+/// returns when the executor's `run` does, for example when the thread running it is asked to stop.
+/// The executors of `embassy-executor` never return from `run`, so the executor type must be
+/// provided via the `executor` argument, and optionally `executor_new`. Its `run` method must take
+/// a closure receiving the `Spawner`, and return `()`. A `run` returning `!` works as well.
+///
+/// The executor is leaked, so this requires `std`.
+///
+/// ## Examples
+/// Spawning a task:
+/// ``` rust,ignore
+/// #[embassy_executor::main_hosted(executor = "my_rtos::Executor")]
+/// async fn main(_s: embassy_executor::Spawner) {
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn main_hosted(args: TokenStream, item: TokenStream) -> TokenStream {
+    main::run(args.into(), item.into(), &main::ARCH_HOSTED).into()
+}
+
 /// Creates a new `executor` instance and declares an application entry point for WASM spawning the corresponding function body as an async task.
 ///
 /// The following restrictions apply:
@@ -184,6 +332,8 @@ pub fn main_std(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. If it returns an `Err`, the main task panics with the error.
+///   The error type must implement `Debug`.
 ///
 /// ## Examples
 /// Spawning a task:
@@ -207,8 +357,15 @@ pub fn main_wasm(args: TokenStream, item: TokenStream) -> TokenStream {
 /// * The function must be declared `async`.
 /// * The function must not use generics.
 /// * Only a single `main` task may be declared.
+/// * The function may return a `Result`. As there is no process to exit, if it returns an `Err`, the
+///   main task panics with the error. The error type must implement `Debug`.
 ///
 /// A user-defined entry macro and executor type must be provided via the `entry` and `executor` arguments of the `main` macro.
+/// The entry macro can also be given as raw tokens, such as `entry(your_hal::entry(core = 1))`.
+///
+/// The executor is created with `Executor::new()`. If it needs constructor arguments, pass the
+/// expression creating it with `executor_new`, which can only be used together with `executor`:
+/// `#[embassy_executor::main(entry = ..., executor = "your_hal::Executor", executor_new = "your_hal::Executor::with_priority(3)")]`.
 ///
 /// ## Examples
 /// Spawning a task:
@@ -10,6 +10,8 @@ use crate::util::*;
 
 enum Flavor {
     Standard,
+    /// The executor's `run` returns, so `main` returns too instead of being `-> !`.
+    Hosted,
     Wasm,
 }
 
@@ -17,62 +19,144 @@ pub(crate) struct Arch {
     default_entry: Option<&'static str>,
     flavor: Flavor,
     executor_required: bool,
+    /// Whether an error returned from `main` exits the process, instead of panicking.
+    exit_on_error: bool,
+    /// Whether the `stack_size` argument is supported, running the executor in a thread of that size.
+    thread_stack_size: bool,
+    /// Whether the `interrupt` argument is supported, running the main task in an `InterruptExecutor`.
+    interrupt_executor: bool,
 }
 
 pub static ARCH_AVR: Arch = Arch {
     default_entry: Some("avr_device::entry"),
     flavor: Flavor::Standard,
     executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 pub static ARCH_RISCV: Arch = Arch {
     default_entry: Some("riscv_rt::entry"),
     flavor: Flavor::Standard,
     executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 pub static ARCH_CORTEX_M: Arch = Arch {
     default_entry: Some("cortex_m_rt::entry"),
     flavor: Flavor::Standard,
     executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: true,
 };
 
 pub static ARCH_CORTEX_AR: Arch = Arch {
     default_entry: None,
     flavor: Flavor::Standard,
     executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 pub static ARCH_SPIN: Arch = Arch {
     default_entry: None,
     flavor: Flavor::Standard,
     executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 pub static ARCH_STD: Arch = Arch {
     default_entry: None,
     flavor: Flavor::Standard,
     executor_required: false,
+    exit_on_error: true,
+    thread_stack_size: true,
+    interrupt_executor: false,
+};
+
+pub static ARCH_HOSTED: Arch = Arch {
+    default_entry: None,
+    flavor: Flavor::Hosted,
+    executor_required: true,
+    exit_on_error: true,
+    thread_stack_size: false,
+    interrupt_executor: false,
+};
+
+pub static ARCH_XTENSA: Arch = Arch {
+    default_entry: Some("xtensa_lx_rt::entry"),
+    flavor: Flavor::Standard,
+    executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 pub static ARCH_WASM: Arch = Arch {
     default_entry: Some("wasm_bindgen::prelude::wasm_bindgen(start)"),
     flavor: Flavor::Wasm,
     executor_required: false,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 pub static ARCH_UNSPECIFIED: Arch = Arch {
     default_entry: None,
     flavor: Flavor::Standard,
     executor_required: true,
+    exit_on_error: false,
+    thread_stack_size: false,
+    interrupt_executor: false,
 };
 
 #[derive(Debug, FromMeta, Default)]
 struct Args {
     #[darling(default)]
-    entry: Option<String>,
+    entry: Option<Entry>,
     #[darling(default)]
     executor: Option<String>,
+    #[darling(default)]
+    executor_new: Option<String>,
+    #[darling(default)]
+    stack_size: Option<usize>,
+    #[darling(default)]
+    interrupt: Option<String>,
+}
+
+/// The contents of the entry attribute, emitted verbatim as `#[...]`.
+///
+/// Either a string, `entry = "cortex_m_rt::entry"`, or the raw tokens in parentheses,
+/// `entry(my_rt::entry(stack_size = 4096))`. The latter avoids escaping quotes in arguments.
+#[derive(Debug)]
+struct Entry(TokenStream);
+
+impl FromMeta for Entry {
+    fn from_meta(item: &syn::Meta) -> darling::Result<Self> {
+        match item {
+            syn::Meta::List(list) => Ok(Self(list.tokens.clone())),
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s), ..
+                }),
+                ..
+            }) => Self::from_string(&s.value()).map_err(|e| e.with_span(s)),
+            _ => Err(darling::Error::unexpected_type("entry").with_span(item)),
+        }
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        TokenStream::from_str(value)
+            .map(Self)
+            .map_err(|e| darling::Error::custom(format!("invalid entry attribute: {e}")))
+    }
 }
 
 pub fn run(args: TokenStream, item: TokenStream, arch: &Arch) -> TokenStream {
@@ -116,33 +200,47 @@ pub fn run(args: TokenStream, item: TokenStream, arch: &Arch) -> TokenStream {
     if !f.sig.variadic.is_none() {
         error(&mut errors, &f.sig, "main function must not be variadic");
     }
+    let mut returns_result = false;
     match &f.sig.output {
         ReturnType::Default => {}
         ReturnType::Type(_, ty) => match &**ty {
             Type::Tuple(tuple) if tuple.elems.is_empty() => {}
             Type::Never(_) => {}
+            Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == "Result") => {
+                returns_result = true;
+            }
             _ => error(
                 &mut errors,
                 &f.sig,
-                "main function must either not return a value, return `()` or return `!`",
+                "main function must either not return a value, return `()`, return `!` or return a `Result`",
             ),
         },
     }
 
     if fargs.len() != 1 {
         error(&mut errors, &f.sig, "main function must have 1 argument: the spawner.");
+    } else if let Some(syn::FnArg::Typed(arg)) = fargs.first() {
+        let is_spawner = match &*arg.ty {
+            Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|s| s.ident == "Spawner"),
+            _ => false,
+        };
+        if !is_spawner {
+            error(
+                &mut errors,
+                &arg.ty,
+                "the argument of the main function must be the spawner: `embassy_executor::Spawner`.",
+            );
+        }
     }
 
-    let entry = match (args.entry.as_deref(), arch.default_entry.as_deref()) {
+    let entry = match (args.entry, arch.default_entry) {
+        (Some(Entry(x)), _) => x,
+        (None, Some(x)) => TokenStream::from_str(x).unwrap(),
         (None, None) => TokenStream::new(),
-        (Some(x), _) | (None, Some(x)) if x == "" => TokenStream::new(),
-        (Some(x), _) | (None, Some(x)) => match TokenStream::from_str(x) {
-            Ok(x) => quote!(#[#x]),
-            Err(e) => {
-                error(&mut errors, &f.sig, e);
-                TokenStream::new()
-            }
-        },
+    };
+    let entry = match entry.is_empty() {
+        true => TokenStream::new(),
+        false => quote!(#[#entry]),
     };
 
     let executor = match (args.executor.as_deref(), arch.executor_required) {
@@ -162,10 +260,89 @@ For example: `#[embassy_executor::main(entry = ..., executor = \"some_crate::Exe
         (None, _) => "::embassy_executor::Executor",
     };
 
-    let executor = TokenStream::from_str(executor).unwrap_or_else(|e| {
-        error(&mut errors, &f.sig, e);
-        TokenStream::new()
-    });
+    // The executor may be generic (e.g. `my_crate::Executor<Config>`), so it is parsed as a type
+    // path and wrapped in `<...>` wherever it's used in expression position.
+    let executor = match syn::parse_str::<syn::TypePath>(executor) {
+        Ok(x) => quote!(<#x>),
+        Err(e) => {
+            if !executor.is_empty() {
+                error(
+                    &mut errors,
+                    &f.sig,
+                    format!("`executor` must be a type path, such as `some_crate::Executor`: {e}"),
+                );
+            }
+            TokenStream::new()
+        }
+    };
+
+    // An expression constructing the executor, for executors which take constructor arguments.
+    let executor_new = match (args.executor_new.as_deref(), args.executor.is_some()) {
+        (None, _) => quote!(#executor::new()),
+        (Some(_), false) => {
+            error(
+                &mut errors,
+                &f.sig,
+                "`executor_new` can only be used together with `executor`, the type it constructs.",
+            );
+            TokenStream::new()
+        }
+        (Some(x), true) => match syn::parse_str::<syn::Expr>(x) {
+            Ok(x) => quote!(#x),
+            Err(e) => {
+                error(
+                    &mut errors,
+                    &f.sig,
+                    format!("`executor_new` must be an expression, such as `some_crate::Executor::new(3)`: {e}"),
+                );
+                TokenStream::new()
+            }
+        },
+    };
+
+    if args.stack_size.is_some() && !arch.thread_stack_size {
+        error(
+            &mut errors,
+            &f.sig,
+            "`stack_size` is only supported with `arch-std`, where the executor runs in a thread.",
+        );
+    }
+
+    // The interrupt running the executor, as a path to an `InterruptNumber` value such as
+    // `pac::Interrupt::SWI0`. Its last segment is the name of the interrupt handler.
+    let interrupt = match (args.interrupt.as_deref(), arch.interrupt_executor) {
+        (None, _) => None,
+        (Some(_), false) => {
+            error(
+                &mut errors,
+                &f.sig,
+                "`interrupt` is only supported with `arch-cortex-m`, where the executor can run in an interrupt.",
+            );
+            None
+        }
+        (Some(_), true) if args.executor.is_some() => {
+            error(
+                &mut errors,
+                &f.sig,
+                "`interrupt` can't be used together with `executor`, as it always uses `InterruptExecutor`.",
+            );
+            None
+        }
+        (Some(x), true) => match syn::parse_str::<syn::Path>(x) {
+            Ok(path) => {
+                let handler = path.segments.last().unwrap().ident.to_string();
+                Some((path, handler))
+            }
+            Err(e) => {
+                error(
+                    &mut errors,
+                    &f.sig,
+                    format!("`interrupt` must be a path, such as `pac::Interrupt::SWI0`: {e}"),
+                );
+                None
+            }
+        },
+    };
 
     let f_body = f.body;
     let out = &f.sig.output;
@@ -186,7 +363,7 @@ For example: `#[embassy_executor::main(entry = ..., executor = \"some_crate::Exe
                     ::core::mem::transmute(t)
                 }
 
-                let mut executor = #executor::new();
+                let mut executor = #executor_new;
                 let executor = unsafe { __make_static(&mut executor) };
                 executor.run(|spawner| {
                     let main_task = __embassy_main(spawner).unwrap();
@@ -195,10 +372,23 @@ For example: `#[embassy_executor::main(entry = ..., executor = \"some_crate::Exe
                 })
             },
         ),
+        Flavor::Hosted => (
+            quote!(()),
+            quote! {
+                // Tasks may still hold wakers pointing into the executor once `run` returns, so
+                // it is leaked rather than kept on the stack of `main`.
+                let executor = ::std::boxed::Box::leak(::std::boxed::Box::new(#executor_new));
+                executor.run(|spawner| {
+                    let main_task = __embassy_main(spawner).unwrap();
+                    #name_main_task
+                    spawner.spawn(main_task);
+                })
+            },
+        ),
         Flavor::Wasm => (
             quote!(Result<(), wasm_bindgen::JsValue>),
             quote! {
-                let executor = ::std::boxed::Box::leak(::std::boxed::Box::new(#executor::new()));
+                let executor = ::std::boxed::Box::leak(::std::boxed::Box::new(#executor_new));
 
                 executor.start(|spawner| {
                     let main_task = __embassy_main(spawner).unwrap();
@@ -211,21 +401,96 @@ For example: `#[embassy_executor::main(entry = ..., executor = \"some_crate::Exe
         ),
     };
 
+    // A `Result` can't be the output of a task, so the body is wrapped in a task handling the error.
+    let mut main_task = match fargs.first() {
+        Some(syn::FnArg::Typed(arg)) if returns_result => {
+            let spawner_ty = &arg.ty;
+            let on_error = if arch.exit_on_error {
+                quote! {
+                    ::std::eprintln!("Error: {:?}", e);
+                    ::std::process::exit(1);
+                }
+            } else {
+                quote! {
+                    ::core::panic!("main task returned an error: {:?}", e);
+                }
+            };
+            quote! {
+                #[::embassy_executor::task()]
+                #[allow(clippy::future_not_send)]
+                async fn __embassy_main(spawner: #spawner_ty) {
+                    #[allow(clippy::future_not_send)]
+                    async fn __embassy_main_inner(#fargs) #out {
+                        #f_body
+                    }
+
+                    if let ::core::result::Result::Err(e) = __embassy_main_inner(spawner).await {
+                        #on_error
+                    }
+                }
+            }
+        }
+        _ => quote! {
+            #[::embassy_executor::task()]
+            #[allow(clippy::future_not_send)]
+            async fn __embassy_main(#fargs) #out {
+                #f_body
+            }
+        },
+    };
+
+    if let Some((irq, handler)) = interrupt {
+        // The spawner passed to `InterruptExecutor::start` can only spawn `Send` tasks, so the main
+        // task is spawned from a task already running in the executor, which can get its `Spawner`.
+        main_task.extend(quote! {
+            #[::embassy_executor::task()]
+            async fn __embassy_main_interrupt() {
+                let spawner = unsafe { ::embassy_executor::Spawner::for_current_executor() }.await;
+                let main_task = __embassy_main(spawner).unwrap();
+                #name_main_task
+                spawner.spawn(main_task);
+            }
+        });
+        main_body = quote! {
+            static EXECUTOR: ::embassy_executor::InterruptExecutor = ::embassy_executor::InterruptExecutor::new();
+
+            #[unsafe(export_name = #handler)]
+            unsafe extern "C" fn __embassy_executor_interrupt() {
+                unsafe { EXECUTOR.on_interrupt() }
+            }
+
+            EXECUTOR.start(#irq).spawn(__embassy_main_interrupt().unwrap());
+            loop {
+                ::embassy_executor::_export::wfi();
+            }
+        };
+    }
+
     let mut main_attrs = TokenStream::new();
     for attr in f.attrs {
         main_attrs.extend(quote!(#attr));
     }
 
+    if let (Some(stack_size), true) = (args.stack_size, arch.thread_stack_size) {
+        main_body = quote! {
+            let thread = ::std::thread::Builder::new()
+                .name(::std::string::String::from("main"))
+                .stack_size(#stack_size)
+                .spawn(move || { #main_body })
+                .expect("failed to spawn the executor thread");
+            match thread.join() {
+                ::core::result::Result::Ok(_) => ::core::unreachable!(),
+                ::core::result::Result::Err(e) => ::std::panic::resume_unwind(e),
+            }
+        };
+    }
+
     if !errors.is_empty() {
         main_body = quote! {loop{}};
     }
 
     let result = quote! {
-        #[::embassy_executor::task()]
-        #[allow(clippy::future_not_send)]
-        async fn __embassy_main(#fargs) #out {
-            #f_body
-        }
+        #main_task
 
         #entry
         #main_attrs
@@ -238,3 +503,164 @@ For example: `#[embassy_executor::main(entry = ..., executor = \"some_crate::Exe
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: TokenStream, arch: &Arch) -> String {
+        let item = quote! {
+            async fn main(_spawner: ::embassy_executor::Spawner) {}
+        };
+        run(args, item, arch).to_string()
+    }
+
+    #[test]
+    fn xtensa_default_entry() {
+        let out = expand(TokenStream::new(), &ARCH_XTENSA);
+        assert!(out.contains("# [xtensa_lx_rt :: entry] fn main () -> !"), "{out}");
+        assert!(!out.contains("compile_error"), "{out}");
+    }
+
+    #[test]
+    fn xtensa_custom_entry() {
+        let out = expand(quote!(entry = "esp_riscv_rt::entry"), &ARCH_XTENSA);
+        assert!(out.contains("# [esp_riscv_rt :: entry] fn main () -> !"), "{out}");
+        assert!(!out.contains("xtensa_lx_rt"), "{out}");
+    }
+
+    #[test]
+    fn entry_with_arguments() {
+        let out = expand(
+            quote!(entry(my_rt::entry(stack_size = 4096, name = "app"))),
+            &ARCH_CORTEX_M,
+        );
+        assert!(
+            out.contains("# [my_rt :: entry (stack_size = 4096 , name = \"app\")] fn main () -> !"),
+            "{out}"
+        );
+        assert!(!out.contains("cortex_m_rt"), "{out}");
+
+        let out = expand(
+            quote!(entry = "my_rt::entry::<Config>(stack_size = 4096)"),
+            &ARCH_CORTEX_M,
+        );
+        assert!(
+            out.contains("# [my_rt :: entry ::< Config > (stack_size = 4096)] fn main () -> !"),
+            "{out}"
+        );
+
+        // An empty entry disables the default one.
+        let out = expand(quote!(entry = ""), &ARCH_CORTEX_M);
+        assert!(out.contains("async fn __embassy_main"), "{out}");
+        assert!(!out.contains("# [cortex_m_rt :: entry]"), "{out}");
+    }
+
+    #[test]
+    fn entry_invalid_tokens() {
+        let out = expand(quote!(entry = "my_rt::entry("), &ARCH_CORTEX_M);
+        assert!(out.contains("compile_error"), "{out}");
+        assert!(out.contains("invalid entry attribute"), "{out}");
+    }
+
+    #[test]
+    fn executor_new() {
+        let out = expand(
+            quote!(executor = "MyExec", executor_new = "MyExec::with_prio(3)"),
+            &ARCH_CORTEX_M,
+        );
+        assert!(out.contains("let mut executor = MyExec :: with_prio (3) ;"), "{out}");
+        assert!(!out.contains(":: new ()"), "{out}");
+        assert!(!out.contains("compile_error"), "{out}");
+
+        let out = expand(quote!(executor = "MyExec"), &ARCH_CORTEX_M);
+        assert!(out.contains("let mut executor = < MyExec > :: new () ;"), "{out}");
+    }
+
+    #[test]
+    fn executor_new_without_executor() {
+        let out = expand(quote!(executor_new = "MyExec::with_prio(3)"), &ARCH_CORTEX_M);
+        assert!(out.contains("compile_error"), "{out}");
+        assert!(out.contains("only be used together with `executor`"), "{out}");
+
+        let out = expand(
+            quote!(executor = "MyExec", executor_new = "MyExec::with_prio("),
+            &ARCH_CORTEX_M,
+        );
+        assert!(out.contains("`executor_new` must be an expression"), "{out}");
+    }
+
+    #[test]
+    fn cortex_m_interrupt() {
+        let out = expand(quote!(interrupt = "pac::Interrupt::SWI0_EGU0"), &ARCH_CORTEX_M);
+        assert!(!out.contains("compile_error"), "{out}");
+        assert!(out.contains("# [cortex_m_rt :: entry] fn main () -> !"), "{out}");
+        assert!(
+            out.contains(
+                "static EXECUTOR : :: embassy_executor :: InterruptExecutor = :: embassy_executor :: InterruptExecutor :: new () ;"
+            ),
+            "{out}"
+        );
+        assert!(
+            out.contains(
+                "# [unsafe (export_name = \"SWI0_EGU0\")] unsafe extern \"C\" fn __embassy_executor_interrupt () { unsafe { EXECUTOR . on_interrupt () } }"
+            ),
+            "{out}"
+        );
+        assert!(
+            out.contains(
+                "EXECUTOR . start (pac :: Interrupt :: SWI0_EGU0) . spawn (__embassy_main_interrupt () . unwrap ()) ;"
+            ),
+            "{out}"
+        );
+        assert!(out.contains("Spawner :: for_current_executor ()"), "{out}");
+        assert!(out.contains(":: embassy_executor :: _export :: wfi ()"), "{out}");
+        assert!(!out.contains(":: cortex_m ::"), "{out}");
+        assert!(!out.contains(". run ("), "{out}");
+    }
+
+    #[test]
+    fn interrupt_unsupported() {
+        let out = expand(quote!(interrupt = "Interrupt::SWI0"), &ARCH_RISCV);
+        assert!(out.contains("only supported with `arch-cortex-m`"), "{out}");
+        assert!(!out.contains("InterruptExecutor"), "{out}");
+
+        let out = expand(
+            quote!(interrupt = "Interrupt::SWI0", executor = "MyExec"),
+            &ARCH_CORTEX_M,
+        );
+        assert!(out.contains("can't be used together with `executor`"), "{out}");
+
+        let out = expand(quote!(interrupt = "Interrupt::"), &ARCH_CORTEX_M);
+        assert!(out.contains("`interrupt` must be a path"), "{out}");
+    }
+
+    #[test]
+    fn std_stack_size() {
+        let out = expand(quote!(stack_size = 8388608), &ARCH_STD);
+        assert!(out.contains(". stack_size (8388608usize)"), "{out}");
+        assert!(!out.contains("compile_error"), "{out}");
+
+        let out = expand(TokenStream::new(), &ARCH_STD);
+        assert!(!out.contains("stack_size"), "{out}");
+    }
+
+    #[test]
+    fn hosted_returning_main() {
+        let out = expand(quote!(executor = "my_rtos::Executor"), &ARCH_HOSTED);
+        assert!(out.contains("fn main () -> () {"), "{out}");
+        assert!(out.contains("Box :: leak"), "{out}");
+        assert!(!out.contains("-> !"), "{out}");
+        assert!(!out.contains("compile_error"), "{out}");
+
+        let out = expand(TokenStream::new(), &ARCH_HOSTED);
+        assert!(out.contains("compile_error"), "{out}");
+    }
+
+    #[test]
+    fn stack_size_unsupported() {
+        let out = expand(quote!(stack_size = 8388608), &ARCH_CORTEX_M);
+        assert!(out.contains("compile_error"), "{out}");
+        assert!(!out.contains(". stack_size"), "{out}");
+    }
+}
@@ -4,8 +4,10 @@ use darling::export::NestedMeta;
 use darling::FromMeta;
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
 use syn::visit::{self, Visit};
-use syn::{Expr, ExprLit, Lit, LitInt, ReturnType, Type, Visibility};
+use syn::visit_mut::{self, VisitMut};
+use syn::{Expr, ExprLit, FnArg, GenericParam, Ident, Lit, LitInt, ReturnType, Token, Type, Visibility};
 
 use crate::util::*;
 
@@ -16,6 +18,15 @@ struct Args {
     /// Use this to override the `embassy_executor` crate path. Defaults to `::embassy_executor`.
     #[darling(default)]
     embassy_executor: Option<syn::Expr>,
+    /// Name set in the metadata of spawned tasks, if the `metadata-name` feature is enabled.
+    #[darling(default)]
+    name: Option<String>,
+    /// Concrete types to instantiate a task generic over one type parameter with, each getting its own pool.
+    #[darling(default)]
+    generics: Option<darling::util::PathList>,
+    /// Also generate a `<task>_in_slot` function, to spawn into a specific slot of the pool.
+    #[darling(default)]
+    slots: bool,
 }
 
 pub fn run(args: TokenStream, item: TokenStream) -> TokenStream {
@@ -42,6 +53,10 @@ pub fn run(args: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
+    let task_name = args.name;
+    let slots = args.slots;
+    let instances = args.generics.map(|g| g.to_vec());
+
     let pool_size = args.pool_size.unwrap_or(Expr::Lit(ExprLit {
         attrs: vec![],
         lit: Lit::Int(LitInt::new("1", Span::call_site())),
@@ -58,8 +73,26 @@ pub fn run(args: TokenStream, item: TokenStream) -> TokenStream {
     if f.sig.asyncness.is_none() && !returns_impl_trait {
         error(&mut errors, &f.sig, "task functions must be async");
     }
-    if !f.sig.generics.params.is_empty() {
-        error(&mut errors, &f.sig, "task functions must not be generic");
+    let mut type_param = None;
+    match &instances {
+        None if !f.sig.generics.params.is_empty() => {
+            error(&mut errors, &f.sig, "task functions must not be generic");
+        }
+        None => {}
+        Some(instances) => {
+            let params = &f.sig.generics.params;
+            match params.first() {
+                Some(GenericParam::Type(param)) if params.len() == 1 => type_param = Some(param.clone()),
+                _ => error(
+                    &mut errors,
+                    &f.sig,
+                    "task functions with `generics` must have exactly one type parameter",
+                ),
+            }
+            if instances.is_empty() {
+                error(&mut errors, &f.sig, "`generics` must list at least one concrete type");
+            }
+        }
     }
     if !f.sig.generics.where_clause.is_none() {
         error(&mut errors, &f.sig, "task functions must not have `where` clauses");
@@ -187,57 +220,213 @@ pub fn run(args: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    let spawn = if returns_impl_trait {
-        quote!(spawn)
+    // Use hygienic identifiers so they can't clash with the task arguments.
+    let slot = format_ident!("slot", span = Span::mixed_site());
+    let token = format_ident!("token", span = Span::mixed_site());
+
+    // Only tasks with `slots` spawn through the pool methods taking a slot, so the error messages of
+    // other tasks don't mention them.
+    let (spawn, slot_arg) = match (returns_impl_trait, slots) {
+        (true, false) => (quote!(spawn), TokenStream::new()),
+        (false, false) => (quote!(_spawn_async_fn), TokenStream::new()),
+        (true, true) => (quote!(_spawn_in_slot), quote!(#slot,)),
+        (false, true) => (quote!(_spawn_async_fn_in_slot), quote!(#slot,)),
+    };
+    // The slot parameter of the functions the task functions dispatch to, if any.
+    let slot_param = if slots {
+        quote!(#slot: ::core::option::Option<usize>,)
     } else {
-        quote!(_spawn_async_fn)
+        TokenStream::new()
     };
 
-    #[cfg(feature = "nightly")]
-    let mut task_outer_body = quote! {
-        trait _EmbassyInternalTaskTrait {
-            type Fut: ::core::future::Future<Output: #embassy_executor::_export::TaskReturnValue> + 'static;
-            fn construct(#fargs) -> Self::Fut;
-        }
+    // The body spawning the task from its pool. `task_inner` is the path of the function creating the
+    // future, with generic arguments, and `fargs` the arguments of the task with concrete types.
+    let task_outer_body = |task_inner: TokenStream, fargs: &Punctuated<FnArg, Token![,]>| {
+        #[cfg(feature = "nightly")]
+        let pool = quote!(POOL);
+        #[cfg(not(feature = "nightly"))]
+        let pool = quote!(__task_pool_get(#task_inner));
+
+        // Pooled tasks get the slot index appended to their name. The names of all slots are built
+        // at compile time, from the pool size.
+        let set_name = match &task_name {
+            Some(name) if cfg!(feature = "metadata-name") => quote! {
+                if let ::core::result::Result::Ok(token) = &#token {
+                    const NAME_LEN: usize = #embassy_executor::_export::pool_task_name_len(#name, POOL_SIZE);
+                    static NAMES: #embassy_executor::_export::PoolTaskNames<POOL_SIZE, NAME_LEN> =
+                        #embassy_executor::_export::PoolTaskNames::new(#name);
+                    if let ::core::option::Option::Some(index) = #pool._slot_of(token) {
+                        token.metadata().set_name(NAMES.get(index));
+                    }
+                }
+            },
+            _ => TokenStream::new(),
+        };
 
-        impl _EmbassyInternalTaskTrait for () {
-            type Fut = impl core::future::Future<Output: #embassy_executor::_export::TaskReturnValue> + 'static;
-            fn construct(#fargs) -> Self::Fut {
-                #task_inner_ident(#(#full_args,)*)
+        #[cfg(feature = "nightly")]
+        let spawn_call =
+            quote!(POOL.#spawn(#slot_arg move || <() as _EmbassyInternalTaskTrait>::construct(#(#full_args,)*)));
+        #[cfg(not(feature = "nightly"))]
+        let spawn_call = quote!(#pool.#spawn(#slot_arg move || #task_inner(#(#full_args,)*)));
+
+        let spawn_and_name = if set_name.is_empty() {
+            quote!(unsafe { #spawn_call })
+        } else {
+            quote! {
+                let #token = unsafe { #spawn_call };
+                #set_name
+                #token
             }
-        }
+        };
 
-        const POOL_SIZE: usize = #pool_size;
-        static POOL: #embassy_executor::raw::TaskPool<<() as _EmbassyInternalTaskTrait>::Fut, POOL_SIZE> = #embassy_executor::raw::TaskPool::new();
-        unsafe { POOL.#spawn(move || <() as _EmbassyInternalTaskTrait>::construct(#(#full_args,)*)) }
-    };
-    #[cfg(not(feature = "nightly"))]
-    let mut task_outer_body = quote! {
-        const fn __task_pool_get<F, Args, Fut>(_: F) -> &'static #embassy_executor::raw::TaskPool<Fut, POOL_SIZE>
-        where
-            F: #embassy_executor::_export::TaskFn<Args, Fut = Fut>,
-            Fut: ::core::future::Future + 'static,
-        {
-            unsafe { &*POOL.get().cast() }
-        }
+        #[cfg(feature = "nightly")]
+        let task_outer_body = quote! {
+            trait _EmbassyInternalTaskTrait {
+                type Fut: ::core::future::Future<Output: #embassy_executor::_export::TaskReturnValue> + 'static;
+                fn construct(#fargs) -> Self::Fut;
+            }
 
-        const POOL_SIZE: usize = #pool_size;
-        static POOL: #embassy_executor::_export::TaskPoolHolder<
-            {#embassy_executor::_export::task_pool_size::<_, _, _, POOL_SIZE>(#task_inner_ident)},
-            {#embassy_executor::_export::task_pool_align::<_, _, _, POOL_SIZE>(#task_inner_ident)},
-        > = unsafe { ::core::mem::transmute(#embassy_executor::_export::task_pool_new::<_, _, _, POOL_SIZE>(#task_inner_ident)) };
-        unsafe { __task_pool_get(#task_inner_ident).#spawn(move || #task_inner_ident(#(#full_args,)*)) }
+            impl _EmbassyInternalTaskTrait for () {
+                type Fut = impl core::future::Future<Output: #embassy_executor::_export::TaskReturnValue> + 'static;
+                fn construct(#fargs) -> Self::Fut {
+                    #task_inner(#(#full_args,)*)
+                }
+            }
+
+            const POOL_SIZE: usize = #pool_size;
+            static POOL: #embassy_executor::raw::TaskPool<<() as _EmbassyInternalTaskTrait>::Fut, POOL_SIZE> = #embassy_executor::raw::TaskPool::new();
+            #spawn_and_name
+        };
+        // The arguments are only needed to name the future type on nightly.
+        #[cfg(not(feature = "nightly"))]
+        let _ = fargs;
+        #[cfg(not(feature = "nightly"))]
+        let task_outer_body = quote! {
+            const fn __task_pool_get<F, Args, Fut>(_: F) -> &'static #embassy_executor::raw::TaskPool<Fut, POOL_SIZE>
+            where
+                F: #embassy_executor::_export::TaskFn<Args, Fut = Fut>,
+                Fut: ::core::future::Future + 'static,
+            {
+                unsafe { &*POOL.get().cast() }
+            }
+
+            const POOL_SIZE: usize = #pool_size;
+            static POOL: #embassy_executor::_export::TaskPoolHolder<
+                {#embassy_executor::_export::task_pool_size::<_, _, _, POOL_SIZE>(#task_inner)},
+                {#embassy_executor::_export::task_pool_align::<_, _, _, POOL_SIZE>(#task_inner)},
+            > = unsafe { ::core::mem::transmute(#embassy_executor::_export::task_pool_new::<_, _, _, POOL_SIZE>(#task_inner)) };
+            #spawn_and_name
+        };
+
+        task_outer_body
     };
 
     let task_outer_attrs = &f.attrs;
+    // The doc comments of the task only apply to the task function itself.
+    let task_slot_attrs = f.attrs.iter().filter(|a| !a.path().is_ident("doc")).collect::<Vec<_>>();
+    let task_spawn_ident = format_ident!("__{}_spawn", task_ident);
+    let task_slot_ident = format_ident!("{}_in_slot", task_ident);
+    let task_slot_doc = format!(
+        "Spawns [`{task_ident}`] into the given slot of its task pool.\n\n\
+         Returns `SpawnError::Busy` if that slot is in use, even if other slots are free.\n\n\
+         # Panics\n\n\
+         Panics if `slot` is not smaller than the pool size of the task."
+    );
+
+    let task_outer = if let (true, Some(param), Some(instances)) = (errors.is_empty(), &type_param, &instances) {
+        // A static can't be generic, so every concrete type gets its own pool, in an impl of a hidden
+        // trait. The task function dispatches to it through a bound on its type parameter.
+        let pool_trait = format_ident!("__{}_pool", task_ident);
+        let slot_none = slots.then(|| quote!(::core::option::Option::None,));
+        let param_ident = &param.ident;
+        let bounds = &param.bounds;
+        let self_ty: Type = syn::parse_quote!(Self);
+        let trait_fargs = substitute_type_param(&fargs, param_ident, &self_ty);
+
+        let impls = instances.iter().map(|path| {
+            let ty: Type = syn::parse_quote!(#path);
+            let fargs = substitute_type_param(&fargs, param_ident, &ty);
+            let body = task_outer_body(quote!(#task_inner_ident::<#ty>), &fargs);
+            quote! {
+                impl #pool_trait for #ty {
+                    #[allow(clippy::too_many_arguments)]
+                    fn spawn(#slot_param #fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> {
+                        #body
+                    }
+                }
+            }
+        });
 
-    if !errors.is_empty() {
-        task_outer_body = quote! {
-            #![allow(unused_variables, unreachable_code)]
-            let _x: ::core::result::Result<#embassy_executor::SpawnToken<()>, #embassy_executor::SpawnError> = ::core::todo!();
-            _x
-        };
-    }
+        let task_slot = slots.then(|| {
+            quote! {
+                #[doc = #task_slot_doc]
+                #[allow(clippy::too_many_arguments)]
+                #(#task_slot_attrs)*
+                #visibility #unsafety fn #task_slot_ident<#param_ident: #pool_trait + #bounds>(#slot: usize, #fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> {
+                    <#param_ident as #pool_trait>::spawn(::core::option::Option::Some(#slot), #(#full_args,)*)
+                }
+            }
+        });
+
+        quote! {
+            // This holds the task pools, one per concrete type.
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            #visibility trait #pool_trait: Sized {
+                #[allow(clippy::too_many_arguments)]
+                fn spawn(#slot_param #trait_fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError>;
+            }
+
+            #(#impls)*
+
+            #(#task_outer_attrs)*
+            #visibility #unsafety fn #task_ident<#param_ident: #pool_trait + #bounds>(#fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> {
+                <#param_ident as #pool_trait>::spawn(#slot_none #(#full_args,)*)
+            }
+
+            #task_slot
+        }
+    } else if errors.is_empty() && slots {
+        let task_outer_body = task_outer_body(quote!(#task_inner_ident), &fargs);
+        quote! {
+            // This holds the task pool, shared by the task function and its `_in_slot` variant.
+            #[doc(hidden)]
+            #[allow(clippy::too_many_arguments)]
+            #(#task_slot_attrs)*
+            #visibility fn #task_spawn_ident #generics (#slot: ::core::option::Option<usize>, #fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> #where_clause{
+                #task_outer_body
+            }
+
+            #(#task_outer_attrs)*
+            #visibility #unsafety fn #task_ident #generics (#fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> #where_clause{
+                #task_spawn_ident(::core::option::Option::None, #(#full_args,)*)
+            }
+
+            #[doc = #task_slot_doc]
+            #[allow(clippy::too_many_arguments)]
+            #(#task_slot_attrs)*
+            #visibility #unsafety fn #task_slot_ident #generics (#slot: usize, #fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> #where_clause{
+                #task_spawn_ident(::core::option::Option::Some(#slot), #(#full_args,)*)
+            }
+        }
+    } else if errors.is_empty() {
+        let task_outer_body = task_outer_body(quote!(#task_inner_ident), &fargs);
+        quote! {
+            #(#task_outer_attrs)*
+            #visibility #unsafety fn #task_ident #generics (#fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> #where_clause{
+                #task_outer_body
+            }
+        }
+    } else {
+        quote! {
+            #(#task_outer_attrs)*
+            #visibility #unsafety fn #task_ident #generics (#fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> #where_clause{
+                #![allow(unused_variables, unreachable_code)]
+                let _x: ::core::result::Result<#embassy_executor::SpawnToken<()>, #embassy_executor::SpawnError> = ::core::todo!();
+                _x
+            }
+        }
+    };
 
     let result = quote! {
         // This is the user's task function, renamed.
@@ -247,10 +436,7 @@ pub fn run(args: TokenStream, item: TokenStream) -> TokenStream {
         #[doc(hidden)]
         #task_inner
 
-        #(#task_outer_attrs)*
-        #visibility #unsafety fn #task_ident #generics (#fargs) -> ::core::result::Result<#embassy_executor::SpawnToken<impl Sized>, #embassy_executor::SpawnError> #where_clause{
-            #task_outer_body
-        }
+        #task_outer
 
         #errors
     };
@@ -258,6 +444,33 @@ pub fn run(args: TokenStream, item: TokenStream) -> TokenStream {
     result
 }
 
+/// Replace the type parameter `param` by `ty` in the task arguments.
+fn substitute_type_param(
+    fargs: &Punctuated<FnArg, Token![,]>,
+    param: &Ident,
+    ty: &Type,
+) -> Punctuated<FnArg, Token![,]> {
+    struct Substitute<'a> {
+        param: &'a Ident,
+        ty: &'a Type,
+    }
+
+    impl VisitMut for Substitute<'_> {
+        fn visit_type_mut(&mut self, i: &mut Type) {
+            match i {
+                Type::Path(path) if path.qself.is_none() && path.path.is_ident(self.param) => *i = self.ty.clone(),
+                _ => visit_mut::visit_type_mut(self, i),
+            }
+        }
+    }
+
+    let mut fargs = fargs.clone();
+    for arg in fargs.iter_mut() {
+        Substitute { param, ty }.visit_fn_arg_mut(arg);
+    }
+    fargs
+}
+
 fn check_arg_ty(errors: &mut TokenStream, ty: &Type) {
     struct Visitor<'a> {
         errors: &'a mut TokenStream,
@@ -52,6 +52,13 @@ mod thread {
             }
         }
 
+        /// Set callbacks to run when the executor goes idle and when it resumes.
+        ///
+        /// See [`raw::Executor::set_idle_hooks`] for details.
+        pub fn set_idle_hooks(&self, on_idle: fn(), on_wake: fn()) {
+            self.inner.set_idle_hooks(on_idle, on_wake)
+        }
+
         /// Run the executor.
         ///
         /// The `init` closure is called with a [`Spawner`] that spawns tasks on
@@ -77,7 +84,7 @@ mod thread {
                 unsafe {
                     self.inner.poll();
                 }
-                wfe();
+                self.inner.idle(wfe);
             }
         }
     }
@@ -49,7 +49,10 @@ mod thread {
     pub(super) const THREAD_PENDER: usize = usize::MAX;
 
     use core::arch::asm;
+    use core::future::Future;
     use core::marker::PhantomData;
+    use core::pin::pin;
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
     pub use embassy_executor_macros::main_cortex_m as main;
 
@@ -79,6 +82,13 @@ mod thread {
             }
         }
 
+        /// Set callbacks to run when the executor goes idle and when it resumes.
+        ///
+        /// See [`raw::Executor::set_idle_hooks`] for details.
+        pub fn set_idle_hooks(&self, on_idle: fn(), on_wake: fn()) {
+            self.inner.set_idle_hooks(on_idle, on_wake)
+        }
+
         /// Run the executor.
         ///
         /// The `init` closure is called with a [`Spawner`] that spawns tasks on
@@ -101,11 +111,34 @@ mod thread {
             init(self.inner.spawner());
 
             loop {
-                unsafe {
-                    self.inner.poll();
-                    asm!("wfe");
-                };
+                unsafe { self.inner.poll() };
+                self.inner.idle(|| unsafe { asm!("wfe") });
+            }
+        }
+    }
+
+    static BLOCK_ON_VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(core::ptr::null(), &BLOCK_ON_VTABLE),
+        |_| unsafe { asm!("sev") },
+        |_| unsafe { asm!("sev") },
+        |_| {},
+    );
+
+    /// Run a future to completion in thread mode, using WFE/SEV.
+    ///
+    /// The core sleeps with `WFE` while the future is pending, and waking it executes `SEV`.
+    ///
+    /// Unlike [`Executor::run`], this returns the future's output. There is no [`Spawner`],
+    /// so this is meant for small programs and tests that drive a single future.
+    pub fn block_on<F: Future>(fut: F) -> F::Output {
+        let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &BLOCK_ON_VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
             }
+            unsafe { asm!("wfe") };
         }
     }
 }
@@ -114,6 +147,10 @@ mod thread {
 pub use interrupt::*;
 #[cfg(feature = "executor-interrupt")]
 mod interrupt {
+    // Without the thread-mode executor, `main` can still run in an interrupt executor.
+    #[cfg(not(feature = "executor-thread"))]
+    pub use embassy_executor_macros::main_cortex_m as main;
+
     use core::cell::{Cell, UnsafeCell};
     use core::mem::MaybeUninit;
 
@@ -5,9 +5,14 @@ compile_error!("`executor-interrupt` is not supported with `arch-spin`.");
 pub use thread::*;
 #[cfg(feature = "executor-thread")]
 mod thread {
+    use core::future::Future;
     use core::marker::PhantomData;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
 
     pub use embassy_executor_macros::main_spin as main;
+    #[cfg(target_arch = "xtensa")]
+    pub use embassy_executor_macros::main_xtensa;
 
     use crate::{raw, Spawner};
 
@@ -55,4 +60,18 @@ mod thread {
             }
         }
     }
+
+    /// Run a future to completion, polling it in a busy loop.
+    ///
+    /// Unlike [`Executor::run`], this returns the future's output. There is no [`Spawner`],
+    /// so this is meant for small programs and tests that drive a single future.
+    pub fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
 }
@@ -5,11 +5,27 @@ compile_error!("`executor-interrupt` is not supported with `arch-std`.");
 pub use thread::*;
 #[cfg(feature = "executor-thread")]
 mod thread {
+    #[cfg(feature = "std-multithread")]
+    use std::cell::Cell;
+    #[cfg(feature = "std-multithread")]
+    use std::collections::VecDeque;
+    use std::future::Future;
     use std::marker::PhantomData;
-    use std::sync::{Condvar, Mutex};
+    #[cfg(feature = "std-multithread")]
+    use std::mem;
+    use std::pin::pin;
+    #[cfg(feature = "std-multithread")]
+    use std::ptr;
+    #[cfg(feature = "std-multithread")]
+    use std::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
 
     pub use embassy_executor_macros::main_std as main;
 
+    #[cfg(feature = "std-multithread")]
+    use crate::SpawnToken;
     use crate::{raw, Spawner};
 
     #[export_name = "__pender"]
@@ -36,6 +52,13 @@ mod thread {
             }
         }
 
+        /// Set callbacks to run when the executor goes idle and when it resumes.
+        ///
+        /// See [`raw::Executor::set_idle_hooks`] for details.
+        pub fn set_idle_hooks(&self, on_idle: fn(), on_wake: fn()) {
+            self.inner.set_idle_hooks(on_idle, on_wake)
+        }
+
         /// Run the executor.
         ///
         /// The `init` closure is called with a [`Spawner`] that spawns tasks on
@@ -59,11 +82,241 @@ mod thread {
 
             loop {
                 unsafe { self.inner.poll() };
-                self.signaler.wait()
+                if self.signaler.is_signaled() {
+                    // There's more work already, don't run the idle hooks.
+                    self.signaler.wait()
+                } else {
+                    self.inner.idle(|| self.signaler.wait())
+                }
+            }
+        }
+
+        /// Run the executor until no task is ready, then return.
+        ///
+        /// The `init` closure is called with a [`Spawner`] that spawns tasks on this executor,
+        /// like in [`run()`](Self::run). Tasks are then polled until all of them are waiting for a
+        /// timer or for some other wake. Calling this again resumes the tasks that have been woken
+        /// in the meantime.
+        ///
+        /// This is meant for tests: combined with a mock time driver, it allows stepping
+        /// through the tasks deterministically, for example with `MockDriver::advance()`.
+        ///
+        /// Tasks keep a reference to the executor, so like [`run()`](Self::run), this requires
+        /// the executor to live forever. It only needs a shared reference though, so that it can
+        /// be called repeatedly.
+        pub fn run_until_idle(&'static self, init: impl FnOnce(Spawner)) {
+            init(self.inner.spawner());
+
+            loop {
+                unsafe { self.inner.poll() };
+                if !self.signaler.take() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Multi-threaded std-based executor.
+    ///
+    /// Runs one raw executor per OS thread, so that CPU-bound tasks end up running in parallel.
+    /// Create one with [`Executor::new_multithread()`].
+    ///
+    /// Each thread has its own queue of tasks that haven't started yet. Tasks spawned from one of
+    /// the executor's threads go to that thread's queue, others are spread across the threads in
+    /// turn. A thread that runs out of work takes the oldest task from its own queue, or else steals
+    /// the newest one from another thread's queue. This way, tasks don't stay stuck behind a thread
+    /// that is busy or blocked.
+    ///
+    /// Tasks are spawned with a [`MultiThreadSpawner`], which only accepts `Send` tasks, like
+    /// [`SendSpawner`](crate::SendSpawner): a task may be started on any of the threads. Once started,
+    /// a task stays on its thread, since only the task's arguments are required to be `Send`, not
+    /// its future. Tasks it spawns with [`Spawner::for_current_executor()`] run on that same thread.
+    #[cfg(feature = "std-multithread")]
+    pub struct MultiThreadExecutor {
+        workers: Box<[Worker]>,
+        /// Worker whose queue gets the next task spawned from outside the executor's threads.
+        next: AtomicUsize,
+    }
+
+    #[cfg(feature = "std-multithread")]
+    struct Worker {
+        inner: raw::Executor,
+        signaler: &'static Signaler,
+        /// Tasks that haven't been started yet. Any worker may take them.
+        queue: Mutex<VecDeque<raw::TaskRef>>,
+        /// Whether the worker is about to wait for work, and may be signaled to take a new task.
+        idle: AtomicBool,
+    }
+
+    #[cfg(feature = "std-multithread")]
+    thread_local! {
+        /// The executor and index of the worker running on the current thread, if any.
+        static CURRENT_WORKER: Cell<Option<(*const MultiThreadExecutor, usize)>> = const { Cell::new(None) };
+    }
+
+    // Each raw executor is only polled and spawned into from its own thread. Tasks in the queues
+    // were spawned through a `MultiThreadSpawner`, so they can be sent to any thread.
+    #[cfg(feature = "std-multithread")]
+    unsafe impl Sync for MultiThreadExecutor {}
+
+    #[cfg(feature = "std-multithread")]
+    impl Executor {
+        /// Create a new multi-threaded executor, running tasks on `threads` OS threads.
+        ///
+        /// See [`MultiThreadExecutor`] for details.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `threads` is zero.
+        pub fn new_multithread(threads: usize) -> MultiThreadExecutor {
+            assert!(threads > 0, "a multi-threaded executor needs at least one thread");
+            let workers = (0..threads)
+                .map(|_| {
+                    let signaler = Box::leak(Box::new(Signaler::new()));
+                    Worker {
+                        inner: raw::Executor::new(signaler as *mut Signaler as *mut ()),
+                        signaler,
+                        queue: Mutex::new(VecDeque::new()),
+                        idle: AtomicBool::new(false),
+                    }
+                })
+                .collect();
+            MultiThreadExecutor {
+                workers,
+                next: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[cfg(feature = "std-multithread")]
+    impl MultiThreadExecutor {
+        /// Run the executor.
+        ///
+        /// The `init` closure is called with a [`MultiThreadSpawner`], use it to spawn the initial
+        /// task(s). Then the worker threads are started, the current thread being one of them.
+        ///
+        /// This function never returns.
+        pub fn run(&'static self, init: impl FnOnce(MultiThreadSpawner)) -> ! {
+            init(MultiThreadSpawner { executor: self });
+
+            for index in 1..self.workers.len() {
+                thread::spawn(move || self.run_worker(index));
+            }
+            self.run_worker(0)
+        }
+
+        fn run_worker(&'static self, index: usize) -> ! {
+            CURRENT_WORKER.with(|current| current.set(Some((self, index))));
+            let worker = &self.workers[index];
+            loop {
+                unsafe { worker.inner.poll() };
+                if worker.signaler.take() {
+                    continue;
+                }
+
+                if let Some(task) = self.take_task(index) {
+                    unsafe { worker.inner.spawn(task) };
+                    continue;
+                }
+
+                // Set the flag before looking at the queues again, and `spawn()` pushes its task
+                // before looking at the flags: with the fences, either this sees the new task, or
+                // `spawn()` sees the flag and signals this worker.
+                worker.idle.store(true, Ordering::Relaxed);
+                fence(Ordering::SeqCst);
+                match self.take_task(index) {
+                    Some(task) => {
+                        worker.idle.store(false, Ordering::Relaxed);
+                        unsafe { worker.inner.spawn(task) }
+                    }
+                    None => {
+                        worker.signaler.wait();
+                        // Woken by a task rather than by `spawn()`, the worker is busy again.
+                        worker.idle.store(false, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+
+        /// Take the oldest task from the worker's own queue, or else steal the newest one from
+        /// another worker.
+        fn take_task(&self, index: usize) -> Option<raw::TaskRef> {
+            if let Some(task) = self.workers[index].queue.lock().unwrap().pop_front() {
+                return Some(task);
+            }
+            let n = self.workers.len();
+            (1..n).find_map(|offset| self.workers[(index + offset) % n].queue.lock().unwrap().pop_back())
+        }
+
+        fn spawn(&self, task: raw::TaskRef) {
+            let index = match CURRENT_WORKER.with(|current| current.get()) {
+                Some((executor, index)) if ptr::eq(executor, self) => index,
+                _ => self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len(),
+            };
+            self.workers[index].queue.lock().unwrap().push_back(task);
+            fence(Ordering::SeqCst);
+
+            // Wake a single idle worker, so that tasks spawned together start on different threads,
+            // preferably the one whose queue has the task. Busy workers check the queues once they
+            // run out of work.
+            let n = self.workers.len();
+            if let Some(worker) = (0..n)
+                .map(|offset| &self.workers[(index + offset) % n])
+                .find(|w| w.idle.swap(false, Ordering::Relaxed))
+            {
+                worker.signaler.signal();
             }
         }
     }
 
+    /// Handle to spawn tasks into a [`MultiThreadExecutor`], from any thread.
+    ///
+    /// Like [`SendSpawner`](crate::SendSpawner), it can only spawn `Send` tasks.
+    #[cfg(feature = "std-multithread")]
+    #[derive(Copy, Clone)]
+    pub struct MultiThreadSpawner {
+        executor: &'static MultiThreadExecutor,
+    }
+
+    #[cfg(feature = "std-multithread")]
+    impl MultiThreadSpawner {
+        /// Spawn a task into the executor.
+        ///
+        /// The task is queued on the current thread if it is one of the executor's threads, and
+        /// starts on the first thread that runs out of work and takes it.
+        pub fn spawn<S: Send>(&self, token: SpawnToken<S>) {
+            let task = token.raw_task;
+            mem::forget(token);
+            self.executor.spawn(task)
+        }
+    }
+
+    /// Run a future to completion on the current thread.
+    ///
+    /// The thread is parked while the future is pending, and unparked when it is woken.
+    ///
+    /// Unlike [`Executor::run`], this returns the future's output. There is no [`Spawner`],
+    /// so this is meant for small programs and tests that drive a single future.
+    pub fn block_on<F: Future>(fut: F) -> F::Output {
+        struct ThreadWaker(Thread);
+
+        impl Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
     struct Signaler {
         mutex: Mutex<bool>,
         condvar: Condvar,
@@ -85,6 +338,15 @@ mod thread {
             *signaled = false;
         }
 
+        fn is_signaled(&self) -> bool {
+            *self.mutex.lock().unwrap()
+        }
+
+        /// Clear the signal without waiting, returning whether it was set.
+        fn take(&self) -> bool {
+            core::mem::take(&mut *self.mutex.lock().unwrap())
+        }
+
         fn signal(&self) {
             let mut signaled = self.mutex.lock().unwrap();
             *signaled = true;
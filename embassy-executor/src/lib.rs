@@ -9,6 +9,7 @@
 // This mod MUST go first, so that the others see its macros.
 pub(crate) mod fmt;
 
+pub use embassy_executor_macros::main_hosted;
 pub use embassy_executor_macros::task;
 
 macro_rules! check_at_most_one {
@@ -57,6 +58,9 @@ pub use spawner::*;
 mod metadata;
 pub use metadata::*;
 
+#[cfg(feature = "metadata-name")]
+mod pool_names;
+
 /// Implementation details for embassy macros.
 /// Do not use. Used for macros and HALs only. Not covered by semver guarantees.
 #[doc(hidden)]
@@ -229,6 +233,13 @@ pub mod _export {
 
     #[allow(dead_code)]
     pub type Never = <fn() -> ! as HasOutput>::Output;
+
+    /// Used by `main` in interrupt mode, so that user crates don't need to depend on `cortex-m`.
+    #[cfg(all(feature = "arch-cortex-m", feature = "executor-interrupt"))]
+    pub use cortex_m::asm::wfi;
+
+    #[cfg(feature = "metadata-name")]
+    pub use crate::pool_names::{pool_task_name_len, PoolTaskNames};
 }
 
 /// Implementation details for embassy macros.
@@ -255,4 +266,11 @@ pub mod _export {
 
     #[allow(dead_code)]
     pub type Never = <fn() -> ! as HasOutput>::Output;
+
+    /// Used by `main` in interrupt mode, so that user crates don't need to depend on `cortex-m`.
+    #[cfg(all(feature = "arch-cortex-m", feature = "executor-interrupt"))]
+    pub use cortex_m::asm::wfi;
+
+    #[cfg(feature = "metadata-name")]
+    pub use crate::pool_names::{pool_task_name_len, PoolTaskNames};
 }
@@ -0,0 +1,63 @@
+//! Names of the tasks of a pool, for the `name` argument of the task macro.
+
+/// Length of the longest name in a pool of `pool_size` tasks named `name`, including the NUL
+/// terminator.
+pub const fn pool_task_name_len(name: &str, pool_size: usize) -> usize {
+    if pool_size <= 1 {
+        return name.len() + 1;
+    }
+    name.len() + 1 + digits(pool_size - 1) + 1
+}
+
+const fn digits(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// The NUL-terminated names of the tasks of a pool of `N` tasks, each `LEN` bytes long at most.
+///
+/// In a pool of more than one task, the index of the slot is appended to the name of every task,
+/// e.g. `uart.0`, `uart.1`. They are built at compile time, as task names must be `'static`.
+pub struct PoolTaskNames<const N: usize, const LEN: usize>([[u8; LEN]; N]);
+
+impl<const N: usize, const LEN: usize> PoolTaskNames<N, LEN> {
+    /// Builds the names. `LEN` must be [`pool_task_name_len(name, N)`](pool_task_name_len).
+    pub const fn new(name: &str) -> Self {
+        assert!(LEN == pool_task_name_len(name, N));
+        let name = name.as_bytes();
+        let mut names = [[0; LEN]; N];
+        let mut slot = 0;
+        while slot < N {
+            let mut i = 0;
+            while i < name.len() {
+                names[slot][i] = name[i];
+                i += 1;
+            }
+            if N > 1 {
+                names[slot][i] = b'.';
+                // Write the digits from the end, the NUL terminator is the first zero after them.
+                let mut end = i + 1 + digits(slot);
+                let mut n = slot;
+                while end > i + 1 {
+                    end -= 1;
+                    names[slot][end] = b'0' + (n % 10) as u8;
+                    n /= 10;
+                }
+            }
+            slot += 1;
+        }
+        Self(names)
+    }
+
+    /// Returns the name of the task in `slot`, including the NUL terminator.
+    pub fn get(&'static self, slot: usize) -> &'static str {
+        let name = &self.0[slot];
+        let len = name.iter().position(|&b| b == 0).unwrap_or(LEN - 1) + 1;
+        // SAFETY: the name is a `str` followed by ASCII characters, and the terminator is ASCII too.
+        unsafe { core::str::from_utf8_unchecked(&name[..len]) }
+    }
+}
@@ -0,0 +1,113 @@
+//! Detection of tasks that keep the executor busy, enabled by the `fairness-debug` feature.
+//!
+//! The executor is cooperative: a task that wakes itself on every poll, for example by busy-awaiting
+//! a flag with `yield_now()`, keeps the executor from ever going idle. This is not a problem for other
+//! tasks, which still get polled in turn, but it wastes power and is usually a bug.
+//!
+//! To detect this, the executor counts the polls of every task since it was last idle. The executor is
+//! idle when a call to `poll()` finishes without any task having been woken in the meantime. When a
+//! task reaches the poll budget, a warning is logged.
+
+use core::sync::atomic::Ordering;
+#[cfg(not(feature = "arch-avr"))]
+use core::sync::atomic::{AtomicBool, AtomicU32};
+
+#[cfg(feature = "arch-avr")]
+use portable_atomic::{AtomicBool, AtomicU32};
+
+use super::util::SyncUnsafeCell;
+use super::TaskRef;
+
+/// Default poll budget of a task, see [`Executor::set_poll_budget`](super::Executor::set_poll_budget).
+pub const DEFAULT_POLL_BUDGET: u32 = 100;
+
+pub(crate) struct Fairness {
+    budget: AtomicU32,
+    exceeded: AtomicU32,
+    /// Set when a task is enqueued, cleared when the executor starts polling.
+    pended: AtomicBool,
+    /// Incremented every time the executor goes idle. Only accessed by the executor thread.
+    epoch: SyncUnsafeCell<u32>,
+}
+
+/// Per-task fairness state. Only accessed by the executor thread.
+pub(crate) struct TaskFairness {
+    epoch: SyncUnsafeCell<u32>,
+    polls: SyncUnsafeCell<u32>,
+}
+
+impl TaskFairness {
+    pub(crate) const fn new() -> Self {
+        Self {
+            epoch: SyncUnsafeCell::new(0),
+            polls: SyncUnsafeCell::new(0),
+        }
+    }
+}
+
+impl Fairness {
+    pub(crate) const fn new() -> Self {
+        Self {
+            budget: AtomicU32::new(DEFAULT_POLL_BUDGET),
+            exceeded: AtomicU32::new(0),
+            pended: AtomicBool::new(false),
+            epoch: SyncUnsafeCell::new(0),
+        }
+    }
+
+    pub(crate) fn budget(&self) -> u32 {
+        self.budget.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_budget(&self, budget: u32) {
+        self.budget.store(budget, Ordering::Relaxed)
+    }
+
+    pub(crate) fn exceeded(&self) -> u32 {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn pend(&self) {
+        self.pended.store(true, Ordering::Relaxed)
+    }
+
+    pub(crate) fn poll_start(&self) {
+        self.pended.store(false, Ordering::Relaxed)
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called by the executor thread.
+    pub(crate) unsafe fn task_polled(&self, task: TaskRef) {
+        let state = &task.header().fairness;
+        let epoch = self.epoch.get();
+        if state.epoch.get() != epoch {
+            state.epoch.set(epoch);
+            state.polls.set(0);
+        }
+
+        let polls = state.polls.get().saturating_add(1);
+        state.polls.set(polls);
+
+        if polls == self.budget() {
+            warn!(
+                "task {} was polled {} times without the executor going idle, it may be busy-waiting",
+                task.id(),
+                polls
+            );
+            // Only the executor thread writes this, so there's no need for an atomic RMW,
+            // which isn't available on all targets.
+            self.exceeded
+                .store(self.exceeded.load(Ordering::Relaxed).wrapping_add(1), Ordering::Relaxed);
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called by the executor thread.
+    pub(crate) unsafe fn poll_end(&self) {
+        if !self.pended.load(Ordering::Relaxed) {
+            self.epoch.set(self.epoch.get().wrapping_add(1));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+
+use critical_section::Mutex;
+
+/// Completion tracking for a task, used by [`JoinHandle`](crate::JoinHandle).
+///
+/// A `TaskStorage` can be respawned once its task has finished, so completion is tracked
+/// with a generation counter that is bumped every time a task running in the storage exits.
+/// A join handle remembers the generation at spawn time, and is complete once it changed.
+///
+/// The same generation check makes sure an abort only applies to the task instance it was requested
+/// for, and not to a later one spawned in the same storage.
+pub(crate) struct JoinState {
+    inner: Mutex<RefCell<Inner>>,
+    /// Set when the current task should be aborted. Only cleared while holding `inner`, when the
+    /// task exits, so that it's read without a critical section every time the task is polled.
+    abort: AtomicBool,
+}
+
+struct Inner {
+    generation: u32,
+    waker: Option<Waker>,
+}
+
+impl JoinState {
+    pub(crate) const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(Inner {
+                generation: 0,
+                waker: None,
+            })),
+            abort: AtomicBool::new(false),
+        }
+    }
+
+    /// Generation of the task currently spawned in the storage.
+    pub(crate) fn generation(&self) -> u32 {
+        critical_section::with(|cs| self.inner.borrow_ref(cs).generation)
+    }
+
+    /// Mark the current task as finished.
+    ///
+    /// Returns the waker of the joiner, if any. It must be woken after the task is despawned.
+    pub(crate) fn complete(&self) -> Option<Waker> {
+        critical_section::with(|cs| {
+            let mut inner = self.inner.borrow_ref_mut(cs);
+            inner.generation = inner.generation.wrapping_add(1);
+            self.abort.store(false, Ordering::Relaxed);
+            inner.waker.take()
+        })
+    }
+
+    /// Request the task of the given generation to be aborted.
+    ///
+    /// Returns whether it's still running, in which case it must be woken to be aborted.
+    pub(crate) fn request_abort(&self, generation: u32) -> bool {
+        critical_section::with(|cs| {
+            let running = self.inner.borrow_ref(cs).generation == generation;
+            if running {
+                self.abort.store(true, Ordering::Relaxed);
+            }
+            running
+        })
+    }
+
+    /// Whether the current task should be aborted instead of polled.
+    pub(crate) fn abort_requested(&self) -> bool {
+        self.abort.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn poll_join(&self, generation: u32, cx: &mut Context<'_>) -> Poll<()> {
+        critical_section::with(|cs| {
+            let mut inner = self.inner.borrow_ref_mut(cs);
+            if inner.generation != generation {
+                return Poll::Ready(());
+            }
+            match &mut inner.waker {
+                Some(w) if w.will_wake(cx.waker()) => {}
+                w => *w = Some(cx.waker().clone()),
+            }
+            Poll::Pending
+        })
+    }
+}
@@ -0,0 +1,97 @@
+//! Task counts of an executor, enabled by the `metrics` feature.
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use core::cell::Cell;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use critical_section::Mutex;
+
+/// Counter updated from any thread or interrupt.
+///
+/// Uses a critical section on targets without atomic read-modify-write operations.
+struct Counter {
+    #[cfg(target_has_atomic = "ptr")]
+    value: AtomicUsize,
+    #[cfg(not(target_has_atomic = "ptr"))]
+    value: Mutex<Cell<usize>>,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Self {
+            #[cfg(target_has_atomic = "ptr")]
+            value: AtomicUsize::new(0),
+            #[cfg(not(target_has_atomic = "ptr"))]
+            value: Mutex::new(Cell::new(0)),
+        }
+    }
+
+    fn get(&self) -> usize {
+        #[cfg(target_has_atomic = "ptr")]
+        return self.value.load(Ordering::Relaxed);
+        #[cfg(not(target_has_atomic = "ptr"))]
+        return critical_section::with(|cs| self.value.borrow(cs).get());
+    }
+
+    fn increment(&self) {
+        #[cfg(target_has_atomic = "ptr")]
+        self.value.fetch_add(1, Ordering::Relaxed);
+        #[cfg(not(target_has_atomic = "ptr"))]
+        critical_section::with(|cs| {
+            let value = self.value.borrow(cs);
+            value.set(value.get() + 1)
+        });
+    }
+
+    fn decrement(&self) {
+        #[cfg(target_has_atomic = "ptr")]
+        self.value.fetch_sub(1, Ordering::Relaxed);
+        #[cfg(not(target_has_atomic = "ptr"))]
+        critical_section::with(|cs| {
+            let value = self.value.borrow(cs);
+            value.set(value.get() - 1)
+        });
+    }
+}
+
+pub(crate) struct Metrics {
+    /// Tasks spawned in the executor that haven't finished.
+    alive: Counter,
+    /// Tasks in the run queue.
+    ready: Counter,
+}
+
+impl Metrics {
+    pub(crate) const fn new() -> Self {
+        Self {
+            alive: Counter::new(),
+            ready: Counter::new(),
+        }
+    }
+
+    pub(crate) fn alive(&self) -> usize {
+        self.alive.get()
+    }
+
+    pub(crate) fn ready(&self) -> usize {
+        self.ready.get()
+    }
+
+    pub(crate) fn task_added(&self) {
+        self.alive.increment()
+    }
+
+    pub(crate) fn task_removed(&self) {
+        self.alive.decrement()
+    }
+
+    pub(crate) fn task_enqueued(&self) {
+        self.ready.increment()
+    }
+
+    pub(crate) fn task_dequeued(&self) {
+        self.ready.decrement()
+    }
+}
@@ -28,6 +28,12 @@ mod waker;
 
 #[cfg(feature = "scheduler-deadline")]
 mod deadline;
+#[cfg(feature = "fairness-debug")]
+mod fairness;
+#[cfg(feature = "join-handle")]
+mod join;
+#[cfg(feature = "metrics")]
+mod metrics;
 
 use core::future::Future;
 use core::marker::PhantomData;
@@ -42,6 +48,8 @@ use core::task::{Context, Poll, Waker};
 #[cfg(feature = "scheduler-deadline")]
 pub(crate) use deadline::Deadline;
 use embassy_executor_timer_queue::TimerQueueItem;
+#[cfg(feature = "fairness-debug")]
+pub use fairness::DEFAULT_POLL_BUDGET;
 #[cfg(feature = "arch-avr")]
 use portable_atomic::AtomicPtr;
 
@@ -108,6 +116,12 @@ pub(crate) struct TaskHeader {
 
     pub(crate) metadata: Metadata,
 
+    #[cfg(feature = "join-handle")]
+    pub(crate) join: join::JoinState,
+
+    #[cfg(feature = "fairness-debug")]
+    pub(crate) fairness: fairness::TaskFairness,
+
     #[cfg(feature = "rtos-trace")]
     all_tasks_next: AtomicPtr<TaskHeader>,
 }
@@ -210,6 +224,10 @@ impl<F: Future + 'static> TaskStorage<F> {
 
                 timer_queue_item: TimerQueueItem::new(),
                 metadata: Metadata::new(),
+                #[cfg(feature = "join-handle")]
+                join: join::JoinState::new(),
+                #[cfg(feature = "fairness-debug")]
+                fairness: fairness::TaskFairness::new(),
                 #[cfg(feature = "rtos-trace")]
                 all_tasks_next: AtomicPtr::new(core::ptr::null_mut()),
             },
@@ -241,29 +259,18 @@ impl<F: Future + 'static> TaskStorage<F> {
     unsafe fn poll(p: TaskRef) {
         let this = &*p.as_ptr().cast::<TaskStorage<F>>();
 
+        // An aborted task exits at a poll boundary, as if its future had completed.
+        #[cfg(feature = "join-handle")]
+        if this.raw.join.abort_requested() {
+            this.exit();
+            return;
+        }
+
         let future = Pin::new_unchecked(this.future.as_mut());
         let waker = waker::from_task(p);
         let mut cx = Context::from_waker(&waker);
         match future.poll(&mut cx) {
-            Poll::Ready(_) => {
-                #[cfg(feature = "_any_trace")]
-                let exec_ptr: *const SyncExecutor = this.raw.executor.load(Ordering::Relaxed);
-
-                // As the future has finished and this function will not be called
-                // again, we can safely drop the future here.
-                this.future.drop_in_place();
-
-                // We replace the poll_fn with a despawn function, so that the task is cleaned up
-                // when the executor polls it next.
-                this.raw.poll_fn.set(Some(poll_exited));
-
-                // Make sure we despawn last, so that other threads can only spawn the task
-                // after we're done with it.
-                this.raw.state.despawn();
-
-                #[cfg(feature = "_any_trace")]
-                trace::task_end(exec_ptr, &p);
-            }
+            Poll::Ready(_) => this.exit(),
             Poll::Pending => {}
         }
 
@@ -272,6 +279,47 @@ impl<F: Future + 'static> TaskStorage<F> {
         mem::forget(waker);
     }
 
+    /// Drop the future and despawn the task.
+    ///
+    /// Safety: must only be called from `poll`, while the task is spawned.
+    unsafe fn exit(&'static self) {
+        #[cfg(feature = "_any_trace")]
+        let exec_ptr: *const SyncExecutor = self.raw.executor.load(Ordering::Relaxed);
+
+        // As the future has finished or was aborted, and this function will not be called
+        // again, we can safely drop the future here.
+        self.future.drop_in_place();
+
+        // We replace the poll_fn with a despawn function, so that the task is cleaned up
+        // when the executor polls it next.
+        self.raw.poll_fn.set(Some(poll_exited));
+
+        #[cfg(feature = "join-handle")]
+        let joiner = self.raw.join.complete();
+
+        #[cfg(feature = "metrics")]
+        self.raw
+            .executor
+            .load(Ordering::Relaxed)
+            .as_ref()
+            .unwrap_unchecked()
+            .metrics
+            .task_removed();
+
+        // Make sure we despawn last, so that other threads can only spawn the task
+        // after we're done with it.
+        self.raw.state.despawn();
+
+        // Wake the joiner only now, so that it can respawn the task right away.
+        #[cfg(feature = "join-handle")]
+        if let Some(joiner) = joiner {
+            joiner.wake();
+        }
+
+        #[cfg(feature = "_any_trace")]
+        trace::task_end(exec_ptr, &TaskRef::new(self));
+    }
+
     #[doc(hidden)]
     #[allow(dead_code)]
     fn _assert_sync(self) {
@@ -365,6 +413,26 @@ impl<F: Future + 'static, const N: usize> TaskPool<F, N> {
 
     fn spawn_impl<T>(&'static self, future: impl FnOnce() -> F) -> Result<SpawnToken<T>, SpawnError> {
         match self.pool.iter().find_map(AvailableTask::claim) {
+            Some(task) => Ok(task.initialize_impl::<T>(future)),
+            None => Err(SpawnError::PoolExhausted { capacity: N }),
+        }
+    }
+
+    fn spawn_in_slot_impl<T>(
+        &'static self,
+        slot: Option<usize>,
+        future: impl FnOnce() -> F,
+    ) -> Result<SpawnToken<T>, SpawnError> {
+        let Some(index) = slot else {
+            return self.spawn_impl::<T>(future);
+        };
+        assert!(
+            index < N,
+            "task pool slot {} out of range, the pool size is {}",
+            index,
+            N
+        );
+        match AvailableTask::claim(&self.pool[index]) {
             Some(task) => Ok(task.initialize_impl::<T>(future)),
             None => Err(SpawnError::Busy),
         }
@@ -375,12 +443,53 @@ impl<F: Future + 'static, const N: usize> TaskPool<F, N> {
     /// See [`TaskStorage::spawn()`] for details.
     ///
     /// This will loop over the pool and spawn the task in the first storage that
-    /// is currently free. If none is free, a "poisoned" SpawnToken is returned,
-    /// which will cause [`Spawner::spawn()`](super::Spawner::spawn) to return the error.
+    /// is currently free. If none is free, [`SpawnError::PoolExhausted`] is returned.
     pub fn spawn(&'static self, future: impl FnOnce() -> F) -> Result<SpawnToken<impl Sized>, SpawnError> {
         self.spawn_impl::<F>(future)
     }
 
+    /// Try to spawn a task in a specific storage of the pool.
+    ///
+    /// See [`TaskStorage::spawn()`] for details.
+    ///
+    /// Unlike [`spawn()`](Self::spawn), this always uses the storage at `index`. This makes
+    /// it possible to map task instances to slots deterministically. If that storage is
+    /// currently in use, [`SpawnError::Busy`] is returned, even if other storages are free.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not smaller than the pool size `N`.
+    pub fn spawn_at(
+        &'static self,
+        index: usize,
+        future: impl FnOnce() -> F,
+    ) -> Result<SpawnToken<impl Sized>, SpawnError> {
+        self.spawn_in_slot_impl::<F>(Some(index), future)
+    }
+
+    /// Returns the index of the storage in the pool that holds the task of `token`.
+    ///
+    /// Not covered by semver guarantees. DO NOT call this directly. Intended to be used
+    /// by the Embassy macros ONLY.
+    #[doc(hidden)]
+    pub fn _slot_of<S>(&self, token: &SpawnToken<S>) -> Option<usize> {
+        let task = token.raw_task.as_ptr();
+        self.pool.iter().position(|storage| core::ptr::eq(&storage.raw, task))
+    }
+
+    /// Like spawn(), but spawns into the storage at `slot` if it is `Some`.
+    ///
+    /// Not covered by semver guarantees. DO NOT call this directly. Intended to be used
+    /// by the Embassy macros ONLY.
+    #[doc(hidden)]
+    pub fn _spawn_in_slot(
+        &'static self,
+        slot: Option<usize>,
+        future: impl FnOnce() -> F,
+    ) -> Result<SpawnToken<impl Sized>, SpawnError> {
+        self.spawn_in_slot_impl::<F>(slot, future)
+    }
+
     /// Like spawn(), but allows the task to be send-spawned if the args are Send even if
     /// the future is !Send.
     ///
@@ -397,6 +506,26 @@ impl<F: Future + 'static, const N: usize> TaskPool<F, N> {
         // See the comment in AvailableTask::__initialize_async_fn for explanation.
         self.spawn_impl::<FutFn>(future)
     }
+
+    /// Like _spawn_async_fn(), but spawns into the storage at `slot` if it is `Some`.
+    ///
+    /// Not covered by semver guarantees. DO NOT call this directly. Intended to be used
+    /// by the Embassy macros ONLY.
+    ///
+    /// SAFETY: `future` must be a closure of the form `move || my_async_fn(args)`, where `my_async_fn`
+    /// is an `async fn`, NOT a hand-written `Future`.
+    #[doc(hidden)]
+    pub unsafe fn _spawn_async_fn_in_slot<FutFn>(
+        &'static self,
+        slot: Option<usize>,
+        future: FutFn,
+    ) -> Result<SpawnToken<impl Sized>, SpawnError>
+    where
+        FutFn: FnOnce() -> F,
+    {
+        // See the comment in AvailableTask::__initialize_async_fn for explanation.
+        self.spawn_in_slot_impl::<FutFn>(slot, future)
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -417,6 +546,20 @@ impl Pender {
 pub(crate) struct SyncExecutor {
     run_queue: RunQueue,
     pender: Pender,
+    /// Only accessed by the executor thread.
+    on_idle: SyncUnsafeCell<Option<fn()>>,
+    /// Only accessed by the executor thread.
+    on_wake: SyncUnsafeCell<Option<fn()>>,
+    /// Only accessed by the executor thread.
+    #[cfg(feature = "trace")]
+    before_poll: SyncUnsafeCell<Option<fn(u32)>>,
+    /// Only accessed by the executor thread.
+    #[cfg(feature = "trace")]
+    after_poll: SyncUnsafeCell<Option<fn(u32)>>,
+    #[cfg(feature = "fairness-debug")]
+    fairness: fairness::Fairness,
+    #[cfg(feature = "metrics")]
+    metrics: metrics::Metrics,
 }
 
 impl SyncExecutor {
@@ -424,6 +567,16 @@ impl SyncExecutor {
         Self {
             run_queue: RunQueue::new(),
             pender,
+            on_idle: SyncUnsafeCell::new(None),
+            on_wake: SyncUnsafeCell::new(None),
+            #[cfg(feature = "trace")]
+            before_poll: SyncUnsafeCell::new(None),
+            #[cfg(feature = "trace")]
+            after_poll: SyncUnsafeCell::new(None),
+            #[cfg(feature = "fairness-debug")]
+            fairness: fairness::Fairness::new(),
+            #[cfg(feature = "metrics")]
+            metrics: metrics::Metrics::new(),
         }
     }
 
@@ -438,7 +591,13 @@ impl SyncExecutor {
         #[cfg(feature = "_any_trace")]
         trace::task_ready_begin(self, &task);
 
+        #[cfg(feature = "metrics")]
+        self.metrics.task_enqueued();
+
         if self.run_queue.enqueue(task, l) {
+            #[cfg(feature = "fairness-debug")]
+            self.fairness.pend();
+
             self.pender.pend();
         }
     }
@@ -451,6 +610,9 @@ impl SyncExecutor {
         #[cfg(feature = "_any_trace")]
         trace::task_new(self, &task);
 
+        #[cfg(feature = "metrics")]
+        self.metrics.task_added();
+
         state::locked(|l| {
             self.enqueue(task, l);
         })
@@ -463,9 +625,15 @@ impl SyncExecutor {
         #[cfg(feature = "_any_trace")]
         trace::poll_start(self);
 
+        #[cfg(feature = "fairness-debug")]
+        self.fairness.poll_start();
+
         self.run_queue.dequeue_all(|p| {
             let task = p.header();
 
+            #[cfg(feature = "metrics")]
+            self.metrics.task_dequeued();
+
             #[cfg(feature = "_any_trace")]
             trace::task_exec_begin(self, &p);
 
@@ -474,8 +642,14 @@ impl SyncExecutor {
 
             #[cfg(feature = "_any_trace")]
             trace::task_exec_end(self, &p);
+
+            #[cfg(feature = "fairness-debug")]
+            self.fairness.task_polled(p);
         });
 
+        #[cfg(feature = "fairness-debug")]
+        self.fairness.poll_end();
+
         #[cfg(feature = "_any_trace")]
         trace::executor_idle(self)
     }
@@ -582,12 +756,158 @@ impl Executor {
         super::Spawner::new(self)
     }
 
+    /// Set callbacks to run around the point where the executor waits for work.
+    ///
+    /// `on_idle` is called when the executor has polled all ready tasks and is about to wait for
+    /// the pender, and `on_wake` when it resumes. This can be used for power management, for
+    /// example to enter a low-power mode or stop peripherals while idle.
+    ///
+    /// The hooks are run by [`idle()`](Self::idle). The thread-mode executors of `arch-std`,
+    /// `arch-cortex-m`, `arch-cortex-ar` and `arch-riscv32` call it; if you write your own
+    /// executor on top of the raw executor, call it at your park point.
+    ///
+    /// The hooks run in the executor thread, outside of [`poll()`](Self::poll). They must not call
+    /// `poll()`, and should be short, as no task can run in the meantime. Waking a task or spawning
+    /// one from `on_idle` is allowed: the pender is called, and the executor resumes right away.
+    /// Note that `on_wake` may run without any work to do, if the executor woke up spuriously,
+    /// and that with `arch-riscv32` the hooks are called with interrupts disabled.
+    pub fn set_idle_hooks(&self, on_idle: fn(), on_wake: fn()) {
+        // SAFETY: the executor is not `Sync`, so this is the executor thread.
+        unsafe {
+            self.inner.on_idle.set(Some(on_idle));
+            self.inner.on_wake.set(Some(on_wake));
+        }
+    }
+
+    /// Set callbacks to run right before and right after every task poll.
+    ///
+    /// The callbacks get the ID of the polled task, as returned by [`TaskRef::id()`]. The ID is
+    /// derived from the location of the task in its pool, so it is the same every time the same
+    /// pool slot is polled, including after the task is respawned. This can be used to measure
+    /// the time spent in every task, by reading a timer in both callbacks.
+    ///
+    /// Every call to `before_poll` is followed by a call to `after_poll` for the same task, before
+    /// any other task is polled. The callbacks run in the executor thread, in the middle of
+    /// [`poll()`](Self::poll), and add to the latency of every task: they must be cheap, and must
+    /// not block.
+    ///
+    /// These callbacks are independent from the `_embassy_trace_*` functions, which are still
+    /// called as well.
+    #[cfg(feature = "trace")]
+    pub fn set_poll_hooks(&self, before_poll: fn(u32), after_poll: fn(u32)) {
+        // SAFETY: the executor is not `Sync`, so this is the executor thread.
+        unsafe {
+            self.inner.before_poll.set(Some(before_poll));
+            self.inner.after_poll.set(Some(after_poll));
+        }
+    }
+
+    /// Wait for work by calling `wait`, surrounded by the idle hooks.
+    ///
+    /// See [`set_idle_hooks()`](Self::set_idle_hooks). `wait` should return once the pender has
+    /// been called, for example by executing a `WFE` instruction.
+    pub fn idle(&self, wait: impl FnOnce()) {
+        // SAFETY: the executor is not `Sync`, so this is the executor thread.
+        let (on_idle, on_wake) = unsafe { (self.inner.on_idle.get(), self.inner.on_wake.get()) };
+        if let Some(on_idle) = on_idle {
+            on_idle();
+        }
+        wait();
+        if let Some(on_wake) = on_wake {
+            on_wake();
+        }
+    }
+
+    /// Get the poll budget of tasks.
+    ///
+    /// When a task is polled this many times without the executor going idle in between, a warning
+    /// is logged, as the task is likely busy-waiting. This is checked only with the `fairness-debug`
+    /// feature. Defaults to [`DEFAULT_POLL_BUDGET`].
+    #[cfg(feature = "fairness-debug")]
+    pub fn poll_budget(&self) -> u32 {
+        self.inner.fairness.budget()
+    }
+
+    /// Set the poll budget of tasks. See [`poll_budget()`](Self::poll_budget).
+    #[cfg(feature = "fairness-debug")]
+    pub fn set_poll_budget(&self, budget: u32) {
+        self.inner.fairness.set_budget(budget)
+    }
+
+    /// Number of times a task exceeded the poll budget.
+    ///
+    /// This counts the warnings logged by the `fairness-debug` feature, and is useful to check for
+    /// busy-waiting tasks in tests.
+    #[cfg(feature = "fairness-debug")]
+    pub fn poll_budget_exceeded(&self) -> u32 {
+        self.inner.fairness.exceeded()
+    }
+
+    /// Number of tasks spawned in this executor that haven't finished yet.
+    ///
+    /// This is a snapshot: tasks may be spawned or finish right after it is taken.
+    #[cfg(feature = "metrics")]
+    pub fn alive_task_count(&self) -> usize {
+        self.inner.metrics.alive()
+    }
+
+    /// Number of tasks in the run queue of this executor, waiting to be polled.
+    ///
+    /// This is a snapshot: tasks may be woken or polled right after it is taken.
+    #[cfg(feature = "metrics")]
+    pub fn ready_task_count(&self) -> usize {
+        self.inner.metrics.ready()
+    }
+
     /// Get a unique ID for this Executor.
     pub fn id(&'static self) -> usize {
         &self.inner as *const SyncExecutor as usize
     }
 }
 
+/// Move a spawned task to another executor.
+///
+/// The task is enqueued in `executor`, which polls it from then on. Wakers registered by the task
+/// keep working, they wake it in its new executor. This can be used to rebalance the load between
+/// executors, for example between a thread-mode and an interrupt executor.
+///
+/// Returns `false`, doing nothing, if the task isn't spawned, because it was never spawned or has
+/// already finished. Also returns `false` if the task is currently waiting in the run queue of its
+/// executor. It can be moved once it has been polled.
+///
+/// # Safety
+///
+/// - The task must not be being polled. Call this either from the thread of the task's current
+///   executor (outside of the task itself), or while that executor is known not to be polling.
+///   This also ensures the task can't finish while it is being moved.
+/// - If `executor` runs in a different thread than the task's current executor, the task's future
+///   must be `Send`.
+pub unsafe fn move_task(task: TaskRef, executor: &'static Executor) -> bool {
+    let header = task.header();
+    // A task that isn't spawned has no executor, or has already been removed from its metrics.
+    if !header.state.spawned() {
+        return false;
+    }
+    let mut moved = false;
+    // Marking the task as run-queued first ensures wakers can't enqueue it in the old executor
+    // while we're moving it.
+    header.state.run_enqueue(|l| {
+        #[cfg(feature = "metrics")]
+        {
+            if let Some(old) = header.executor.load(Ordering::Relaxed).as_ref() {
+                old.metrics.task_removed();
+            }
+            executor.inner.metrics.task_added();
+        }
+        header
+            .executor
+            .store((&executor.inner as *const SyncExecutor).cast_mut(), Ordering::Relaxed);
+        executor.inner.enqueue(task, l);
+        moved = true;
+    });
+    moved
+}
+
 /// Wake a task by `TaskRef`.
 ///
 /// You can obtain a `TaskRef` from a `Waker` using [`task_from_waker`].
@@ -611,6 +931,8 @@ pub fn wake_task_no_pend(task: TaskRef) {
         // We have just marked the task as scheduled, so enqueue it.
         unsafe {
             let executor = header.executor.load(Ordering::Relaxed).as_ref().unwrap_unchecked();
+            #[cfg(feature = "metrics")]
+            executor.metrics.task_enqueued();
             executor.run_queue.enqueue(task, l);
         }
     });
@@ -51,6 +51,12 @@ impl State {
         self.state.fetch_and(!STATE_SPAWNED, Ordering::AcqRel);
     }
 
+    /// Return whether the task is spawned.
+    #[inline(always)]
+    pub fn spawned(&self) -> bool {
+        self.state.load(Ordering::Acquire) & STATE_SPAWNED != 0
+    }
+
     /// Mark the task as run-queued if it's spawned and isn't already run-queued. Run the given
     /// function if the task was successfully marked.
     #[inline(always)]
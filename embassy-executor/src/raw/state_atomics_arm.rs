@@ -62,6 +62,14 @@ impl State {
         self.spawned.store(false, Ordering::Relaxed);
     }
 
+    /// Return whether the task is spawned.
+    #[inline(always)]
+    pub fn spawned(&self) -> bool {
+        let r = self.spawned.load(Ordering::Relaxed);
+        compiler_fence(Ordering::Acquire);
+        r
+    }
+
     /// Mark the task as run-queued if it's spawned and isn't already run-queued. Run the given
     /// function if the task was successfully marked.
     #[inline(always)]
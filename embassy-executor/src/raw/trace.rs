@@ -22,6 +22,10 @@
 //! Callbacks can be used by enabling the `trace` feature, and providing implementations of the
 //! `extern "Rust"` functions below. All callbacks must be implemented.
 //!
+//! For profiling, the `trace` feature also lets you register callbacks that are run right before
+//! and right after every task poll, with
+//! [`Executor::set_poll_hooks`](super::Executor::set_poll_hooks).
+//!
 //! ## Task Tracing lifecycle
 //!
 //! ```text
@@ -284,12 +288,22 @@ pub(crate) fn task_exec_begin(executor: &SyncExecutor, task: &TaskRef) {
     unsafe {
         _embassy_trace_task_exec_begin(executor as *const _ as u32, task.as_ptr() as u32)
     }
+    // SAFETY: tasks are only polled by the executor thread.
+    #[cfg(feature = "trace")]
+    if let Some(before_poll) = unsafe { executor.before_poll.get() } {
+        before_poll(task.id())
+    }
     #[cfg(feature = "rtos-trace")]
     rtos_trace::trace::task_exec_begin(task.as_ptr() as u32);
 }
 
 #[inline]
 pub(crate) fn task_exec_end(executor: &SyncExecutor, task: &TaskRef) {
+    // SAFETY: tasks are only polled by the executor thread.
+    #[cfg(feature = "trace")]
+    if let Some(after_poll) = unsafe { executor.after_poll.get() } {
+        after_poll(task.id())
+    }
     #[cfg(feature = "trace")]
     unsafe {
         _embassy_trace_task_exec_end(executor as *const _ as u32, task.as_ptr() as u32)
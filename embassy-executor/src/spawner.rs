@@ -1,7 +1,11 @@
 use core::future::{poll_fn, Future};
 use core::marker::PhantomData;
 use core::mem;
+#[cfg(feature = "join-handle")]
+use core::pin::Pin;
 use core::sync::atomic::Ordering;
+#[cfg(feature = "join-handle")]
+use core::task::Context;
 use core::task::Poll;
 
 use super::raw;
@@ -17,6 +21,13 @@ use crate::Metadata;
 /// in other threads or not. If `S: Send`, it can, which allows spawning it into a [`SendSpawner`].
 /// If not, it can't, so it can only be spawned into the current thread's executor, with [`Spawner`].
 ///
+/// A token doesn't have to be spawned right away: it can be stored, for example in a struct field,
+/// and spawned later. Task functions return a `SpawnToken` of an unnameable type, use
+/// [`erase()`](SpawnToken::erase) to get a token of a type you can name, or to store tokens
+/// of different tasks together. A token is not `Send`, so it must be spawned in the thread
+/// it was created in, even if it is for a `Send` task. Every stored token must be spawned in the
+/// end, see the panics section below.
+///
 /// # Panics
 ///
 /// Dropping a SpawnToken instance panics. You may not "abort" spawning a task in this way.
@@ -46,6 +57,26 @@ impl<S> SpawnToken<S> {
     pub fn metadata(&self) -> &Metadata {
         self.raw_task.metadata()
     }
+
+    /// Erase the task type of the token.
+    ///
+    /// This gives a token of a nameable type, which can be stored in a struct field or in an array
+    /// together with tokens of other tasks, to be spawned later with [`Spawner::spawn()`]. Whether the
+    /// task is `Send` is forgotten, so an erased token can't be spawned with a [`SendSpawner`].
+    ///
+    /// A stored token must still be spawned eventually, as dropping it panics. To spawn tasks
+    /// depending on a runtime condition, such as the hardware that was detected, only call the task
+    /// functions of the tasks that will be spawned.
+    pub fn erase(self) -> SpawnToken<Erased> {
+        let raw_task = self.raw_task;
+        mem::forget(self);
+        unsafe { SpawnToken::new(raw_task) }
+    }
+}
+
+/// Task type of a [`SpawnToken`] erased with [`SpawnToken::erase()`].
+pub struct Erased {
+    _not_send: PhantomData<*mut ()>,
 }
 
 impl<S> Drop for SpawnToken<S> {
@@ -57,13 +88,23 @@ impl<S> Drop for SpawnToken<S> {
 
 /// Error returned when spawning a task.
 #[derive(Copy, Clone)]
+#[non_exhaustive]
 pub enum SpawnError {
-    /// Too many instances of this task are already running.
+    /// The task storage is already in use.
+    ///
+    /// Returned when spawning into a single [`TaskStorage`](crate::raw::TaskStorage), or into a
+    /// specific slot of a pool which is in use, even if other slots are free.
+    Busy,
+    /// Too many instances of this task are already running: all `capacity` storages of its pool
+    /// are in use.
     ///
     /// By default, a task marked with `#[embassy_executor::task]` can only have one instance
     /// running at a time. You may allow multiple instances to run in parallel with
     /// `#[embassy_executor::task(pool_size = 4)]`, at the cost of higher RAM usage.
-    Busy,
+    PoolExhausted {
+        /// The number of task instances the pool can hold, its `pool_size`.
+        capacity: usize,
+    },
 }
 
 impl core::fmt::Debug for SpawnError {
@@ -75,7 +116,8 @@ impl core::fmt::Debug for SpawnError {
 impl core::fmt::Display for SpawnError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            SpawnError::Busy => write!(f, "Busy - Too many instances of this task are already running. Check the `pool_size` attribute of the task."),
+            SpawnError::Busy => write!(f, "Busy - This task storage is already in use."),
+            SpawnError::PoolExhausted { capacity } => write!(f, "PoolExhausted - All {} instances of this task are already running. Check the `pool_size` attribute of the task.", capacity),
         }
     }
 }
@@ -84,13 +126,78 @@ impl core::fmt::Display for SpawnError {
 impl defmt::Format for SpawnError {
     fn format(&self, f: defmt::Formatter) {
         match self {
-            SpawnError::Busy => defmt::write!(f, "Busy - Too many instances of this task are already running. Check the `pool_size` attribute of the task."),
+            SpawnError::Busy => defmt::write!(f, "Busy - This task storage is already in use."),
+            SpawnError::PoolExhausted { capacity } => defmt::write!(f, "PoolExhausted - All {=usize} instances of this task are already running. Check the `pool_size` attribute of the task.", capacity),
         }
     }
 }
 
 impl core::error::Error for SpawnError {}
 
+/// Future that completes when a spawned task finishes running.
+///
+/// Obtained from [`Spawner::spawn_joinable()`] or [`SendSpawner::spawn_joinable()`]. Any task can be
+/// joined, no changes to the `#[embassy_executor::task]` declaration are needed.
+///
+/// Tasks are `'static` and can't return a value, so joining only tells you that the task's future
+/// returned. If you need its result, send it through a channel or a `static` before returning.
+///
+/// The handle refers to the task's storage, which may be reused to spawn the task again once it has
+/// finished. The handle keeps tracking the instance it was created for: it completes once that
+/// instance finishes, even if the storage is respawned before the handle is polled.
+///
+/// Dropping the handle detaches the task: it keeps running, and nothing waits for it. To stop it
+/// instead, use [`abort()`](Self::abort).
+#[cfg(feature = "join-handle")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinHandle {
+    raw_task: raw::TaskRef,
+    generation: u32,
+}
+
+#[cfg(feature = "join-handle")]
+impl JoinHandle {
+    fn new<S>(token: &SpawnToken<S>) -> Self {
+        let raw_task = token.raw_task;
+        Self {
+            raw_task,
+            generation: raw_task.header().join.generation(),
+        }
+    }
+
+    /// Returns the ID of the joined task.
+    pub fn id(&self) -> u32 {
+        self.raw_task.id()
+    }
+
+    /// Aborts the task.
+    ///
+    /// The task is woken, and the next time its executor would poll it, its future is dropped
+    /// instead, as if it had returned. Its storage is then free to spawn it again, and the handle
+    /// completes. Nothing happens if the task has already finished.
+    ///
+    /// This is safe to call from any thread or task, including the aborted task itself. The future
+    /// is always dropped by its executor, between two polls, never while it is running, so that
+    /// non-`Send` tasks are dropped on their own thread. The task can't observe the abort: code
+    /// after its current `.await` point simply never runs. Only `Drop` implementations run, so any
+    /// operation in progress, such as a transfer or a multi-step I/O exchange, may be left
+    /// incomplete. Use a cooperative signal instead if the task needs to clean up.
+    pub fn abort(&self) {
+        if self.raw_task.header().join.request_abort(self.generation) {
+            raw::wake_task(self.raw_task);
+        }
+    }
+}
+
+#[cfg(feature = "join-handle")]
+impl Future for JoinHandle {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.raw_task.header().join.poll_join(self.generation, cx)
+    }
+}
+
 /// Handle to spawn tasks into an executor.
 ///
 /// This Spawner can spawn any task (Send and non-Send ones), but it can
@@ -159,6 +266,17 @@ impl Spawner {
         unsafe { self.executor.spawn(task) }
     }
 
+    /// Spawn a task into an executor, returning a handle to wait for it to finish.
+    ///
+    /// This is like [`spawn()`](Self::spawn), but the returned [`JoinHandle`] resolves when the
+    /// task's future returns, and can [`abort()`](JoinHandle::abort) it. Dropping the handle detaches the task.
+    #[cfg(feature = "join-handle")]
+    pub fn spawn_joinable<S>(&self, token: SpawnToken<S>) -> JoinHandle {
+        let handle = JoinHandle::new(&token);
+        self.spawn(token);
+        handle
+    }
+
     /// Convert this Spawner to a SendSpawner. This allows you to send the
     /// spawner to other threads, but the spawner loses the ability to spawn
     /// non-Send tasks.
@@ -170,6 +288,22 @@ impl Spawner {
     pub fn executor_id(&self) -> usize {
         self.executor.id()
     }
+
+    /// Number of tasks spawned in this spawner's executor that haven't finished yet.
+    ///
+    /// See [`raw::Executor::alive_task_count()`].
+    #[cfg(feature = "metrics")]
+    pub fn alive_task_count(&self) -> usize {
+        self.executor.alive_task_count()
+    }
+
+    /// Number of tasks waiting to be polled in this spawner's executor.
+    ///
+    /// See [`raw::Executor::ready_task_count()`].
+    #[cfg(feature = "metrics")]
+    pub fn ready_task_count(&self) -> usize {
+        self.executor.ready_task_count()
+    }
 }
 
 /// Handle to spawn tasks into an executor from any thread.
@@ -219,4 +353,15 @@ impl SendSpawner {
         mem::forget(token);
         unsafe { self.executor.spawn(header) }
     }
+
+    /// Spawn a task into an executor, returning a handle to wait for it to finish.
+    ///
+    /// This is like [`spawn()`](Self::spawn), but the returned [`JoinHandle`] resolves when the
+    /// task's future returns, and can [`abort()`](JoinHandle::abort) it. Dropping the handle detaches the task.
+    #[cfg(feature = "join-handle")]
+    pub fn spawn_joinable<S: Send>(&self, token: SpawnToken<S>) -> JoinHandle {
+        let handle = JoinHandle::new(&token);
+        self.spawn(token);
+        handle
+    }
 }
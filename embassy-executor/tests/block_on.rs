@@ -0,0 +1,40 @@
+#![cfg(all(feature = "arch-std", feature = "executor-thread"))]
+
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+use embassy_executor::block_on;
+
+#[test]
+fn block_on_ready() {
+    assert_eq!(block_on(async { 42 }), 42);
+}
+
+#[test]
+fn block_on_woken_from_other_thread() {
+    let done = Arc::new(AtomicBool::new(false));
+    let mut started = false;
+
+    let fut = poll_fn(|cx| {
+        if done.load(Ordering::SeqCst) {
+            return Poll::Ready("done");
+        }
+        if !started {
+            started = true;
+            let done = done.clone();
+            let waker = cx.waker().clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                done.store(true, Ordering::SeqCst);
+                waker.wake();
+            });
+        }
+        Poll::Pending
+    });
+
+    assert_eq!(block_on(fut), "done");
+}
@@ -0,0 +1,201 @@
+#![cfg(all(feature = "arch-std", feature = "executor-thread"))]
+
+use std::boxed::Box;
+use std::sync::{Arc, Mutex};
+
+use embassy_executor::{task, Executor};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, MockDriver, Timer};
+
+#[derive(Clone)]
+struct Trace {
+    trace: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Trace {
+    fn new() -> Self {
+        Self {
+            trace: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+    fn push(&self, value: &'static str) {
+        self.trace.lock().unwrap().push(value)
+    }
+
+    fn take(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.trace.lock().unwrap())
+    }
+}
+
+/// Tests using the mock time driver, which is global, must not run in parallel.
+static MOCK_DRIVER: Mutex<()> = Mutex::new(());
+
+#[test]
+fn run_until_idle() {
+    #[task]
+    async fn ticker(trace: Trace) {
+        for _ in 0..2 {
+            trace.push("tick");
+            Timer::after(Duration::from_secs(1)).await;
+        }
+        trace.push("ticker done");
+    }
+
+    #[task]
+    async fn waiter(trace: Trace, signal: &'static Signal<CriticalSectionRawMutex, ()>) {
+        signal.wait().await;
+        trace.push("signaled");
+    }
+
+    let _guard = MOCK_DRIVER.lock().unwrap();
+    let driver = MockDriver::get();
+    driver.reset();
+
+    let executor: &'static Executor = Box::leak(Box::new(Executor::new()));
+    let signal = Box::leak(Box::new(Signal::new()));
+    let trace = Trace::new();
+
+    executor.run_until_idle(|spawner| {
+        spawner.spawn(ticker(trace.clone()).unwrap());
+        spawner.spawn(waiter(trace.clone(), signal).unwrap());
+    });
+    assert_eq!(trace.take(), ["tick"]);
+
+    // Nothing is ready until time passes.
+    executor.run_until_idle(|_| {});
+    driver.advance(Duration::from_millis(999));
+    executor.run_until_idle(|_| {});
+    assert!(trace.take().is_empty());
+
+    driver.advance(Duration::from_millis(1));
+    executor.run_until_idle(|_| {});
+    assert_eq!(trace.take(), ["tick"]);
+
+    // External wake.
+    signal.signal(());
+    executor.run_until_idle(|_| {});
+    assert_eq!(trace.take(), ["signaled"]);
+
+    driver.advance(Duration::from_secs(1));
+    executor.run_until_idle(|_| {});
+    assert_eq!(trace.take(), ["ticker done"]);
+}
+
+#[test]
+fn mock_driver_auto_advance() {
+    use std::sync::mpsc::{self, Sender};
+    use std::thread;
+
+    use embassy_time::Instant;
+
+    #[task]
+    async fn sleeper(done: Sender<Instant>) {
+        Timer::after_secs(3600).await;
+        done.send(Instant::now()).unwrap();
+    }
+
+    let _guard = MOCK_DRIVER.lock().unwrap();
+    let driver = MockDriver::get();
+    driver.reset();
+    driver.set_auto_advance(true);
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let executor: &'static mut Executor = Box::leak(Box::new(Executor::new()));
+        executor.set_idle_hooks(
+            || {
+                MockDriver::get().on_idle();
+            },
+            || {},
+        );
+        executor.run(|spawner| spawner.spawn(sleeper(tx).unwrap()))
+    });
+
+    // The executor goes idle waiting for the timer, and the idle hook advances the time.
+    let woken_at = rx.recv_timeout(std::time::Duration::from_secs(10)).unwrap();
+    assert_eq!(woken_at, Instant::from_secs(3600));
+    driver.set_auto_advance(false);
+}
+
+#[cfg(feature = "std-multithread")]
+#[test]
+fn multithread() {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{self, Sender};
+    use std::thread::{self, ThreadId};
+    use std::time::{Duration, Instant};
+
+    use embassy_executor::MultiThreadExecutor;
+
+    const THREADS: usize = 4;
+    static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    #[task(pool_size = THREADS)]
+    async fn busy(done: Sender<ThreadId>) {
+        // Spin without yielding until all tasks run at the same time, which is only possible if
+        // they run on different threads.
+        RUNNING.fetch_add(1, Ordering::SeqCst);
+        let start = Instant::now();
+        while RUNNING.load(Ordering::SeqCst) < THREADS && start.elapsed() < Duration::from_secs(10) {
+            std::hint::spin_loop();
+        }
+        done.send(thread::current().id()).unwrap();
+    }
+
+    let executor: &'static MultiThreadExecutor = Box::leak(Box::new(Executor::new_multithread(THREADS)));
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        executor.run(|spawner| {
+            for _ in 0..THREADS {
+                spawner.spawn(busy(tx.clone()).unwrap());
+            }
+        })
+    });
+
+    let threads: HashSet<ThreadId> = (0..THREADS)
+        .map(|_| rx.recv_timeout(Duration::from_secs(20)).unwrap())
+        .collect();
+    assert_eq!(RUNNING.load(Ordering::SeqCst), THREADS);
+    assert_eq!(threads.len(), THREADS);
+}
+
+#[cfg(feature = "std-multithread")]
+#[test]
+fn multithread_steal() {
+    use std::sync::mpsc::{self, Sender};
+    use std::thread::{self, ThreadId};
+    use std::time::Duration;
+
+    use embassy_executor::{MultiThreadExecutor, MultiThreadSpawner};
+
+    const TASKS: usize = 8;
+
+    #[task(pool_size = TASKS)]
+    async fn worker(done: Sender<ThreadId>) {
+        done.send(thread::current().id()).unwrap();
+    }
+
+    #[task]
+    async fn blocker(spawner: MultiThreadSpawner, result: Sender<(ThreadId, Vec<ThreadId>)>) {
+        // Spawned from a worker thread, the tasks are queued on this thread, which then blocks
+        // until they are done. They can only run if the other threads steal them.
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..TASKS {
+            spawner.spawn(worker(tx.clone()).unwrap());
+        }
+        let threads = (0..TASKS)
+            .map_while(|_| rx.recv_timeout(Duration::from_secs(10)).ok())
+            .collect();
+        result.send((thread::current().id(), threads)).unwrap();
+    }
+
+    let executor: &'static MultiThreadExecutor = Box::leak(Box::new(Executor::new_multithread(3)));
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || executor.run(|spawner| spawner.spawn(blocker(spawner, tx).unwrap())));
+
+    let (blocked, threads) = rx.recv_timeout(Duration::from_secs(20)).unwrap();
+    assert_eq!(threads.len(), TASKS);
+    assert!(!threads.contains(&blocked));
+}
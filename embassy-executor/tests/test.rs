@@ -7,7 +7,7 @@ use std::sync::{Arc, Mutex};
 use std::task::Poll;
 
 use embassy_executor::raw::Executor;
-use embassy_executor::{task, Spawner};
+use embassy_executor::{task, SpawnError, Spawner};
 
 #[export_name = "__pender"]
 fn __pender(context: *mut ()) {
@@ -17,6 +17,24 @@ fn __pender(context: *mut ()) {
     }
 }
 
+#[cfg(feature = "trace")]
+mod trace_callbacks {
+    #[export_name = "_embassy_trace_poll_start"]
+    fn poll_start(_executor_id: u32) {}
+    #[export_name = "_embassy_trace_task_new"]
+    fn task_new(_executor_id: u32, _task_id: u32) {}
+    #[export_name = "_embassy_trace_task_end"]
+    fn task_end(_executor_id: u32, _task_id: u32) {}
+    #[export_name = "_embassy_trace_task_exec_begin"]
+    fn task_exec_begin(_executor_id: u32, _task_id: u32) {}
+    #[export_name = "_embassy_trace_task_exec_end"]
+    fn task_exec_end(_executor_id: u32, _task_id: u32) {}
+    #[export_name = "_embassy_trace_task_ready_begin"]
+    fn task_ready_begin(_executor_id: u32, _task_id: u32) {}
+    #[export_name = "_embassy_trace_executor_idle"]
+    fn executor_idle(_executor_id: u32) {}
+}
+
 #[derive(Clone)]
 struct Trace {
     trace: Arc<Mutex<Vec<&'static str>>>,
@@ -356,3 +374,607 @@ fn task_metadata() {
     executor.spawner().spawn(task1(None).unwrap());
     unsafe { executor.poll() };
 }
+
+#[test]
+fn spawn_in_slot() {
+    #[task(pool_size = 3, slots)]
+    async fn task1() {
+        poll_fn(|_| Poll::<()>::Pending).await
+    }
+
+    let (executor, _) = setup();
+
+    let token2 = task1_in_slot(2).unwrap();
+    let id2 = token2.id();
+    executor.spawner().spawn(token2);
+    assert!(task1_in_slot(2).is_err());
+
+    let token0 = task1_in_slot(0).unwrap();
+    let id0 = token0.id();
+    executor.spawner().spawn(token0);
+
+    // A plain spawn takes the only free slot, the one in between.
+    let token1 = task1().unwrap();
+    let id1 = token1.id();
+    executor.spawner().spawn(token1);
+    assert_eq!(id1 - id0, id2 - id1);
+    assert!(id0 < id1);
+
+    assert!(task1().is_err());
+    assert!(task1_in_slot(1).is_err());
+    unsafe { executor.poll() };
+}
+
+#[test]
+fn pool_exhausted() {
+    #[task(pool_size = 2, slots)]
+    async fn task1() {
+        poll_fn(|_| Poll::<()>::Pending).await
+    }
+
+    #[task]
+    async fn task2() {
+        poll_fn(|_| Poll::<()>::Pending).await
+    }
+
+    let (executor, _) = setup();
+    let spawner = executor.spawner();
+
+    spawner.spawn(task1().unwrap());
+    spawner.spawn(task1().unwrap());
+    let err = task1().err().unwrap();
+    assert!(matches!(err, SpawnError::PoolExhausted { capacity: 2 }));
+    assert_eq!(
+        err.to_string(),
+        "PoolExhausted - All 2 instances of this task are already running. Check the `pool_size` attribute of the task."
+    );
+
+    spawner.spawn(task2().unwrap());
+    assert!(matches!(task2(), Err(SpawnError::PoolExhausted { capacity: 1 })));
+
+    // Spawning into a specific slot reports that slot as busy.
+    assert!(matches!(task1_in_slot(0), Err(SpawnError::Busy)));
+    unsafe { executor.poll() };
+}
+
+#[cfg(feature = "metadata-name")]
+#[test]
+fn task_name_attribute() {
+    #[task(name = "single")]
+    async fn task1() {}
+
+    #[task(pool_size = 2, name = "pooled", slots)]
+    async fn task2() {
+        poll_fn(|_| Poll::<()>::Pending).await
+    }
+
+    let (executor, _) = setup();
+
+    let token = task1().unwrap();
+    assert_eq!(token.metadata().name(), Some("single\0"));
+    executor.spawner().spawn(token);
+
+    let token = task2_in_slot(1).unwrap();
+    assert_eq!(token.metadata().name(), Some("pooled.1\0"));
+    executor.spawner().spawn(token);
+
+    let token = task2().unwrap();
+    assert_eq!(token.metadata().name(), Some("pooled.0\0"));
+    executor.spawner().spawn(token);
+
+    // The pool size doesn't need to be a literal.
+    const MANY: usize = 12;
+    #[task(pool_size = MANY, name = "many", slots)]
+    async fn task3() {
+        poll_fn(|_| Poll::<()>::Pending).await
+    }
+
+    let token = task3_in_slot(11).unwrap();
+    assert_eq!(token.metadata().name(), Some("many.11\0"));
+    executor.spawner().spawn(token);
+
+    let token = task3_in_slot(7).unwrap();
+    assert_eq!(token.metadata().name(), Some("many.7\0"));
+    executor.spawner().spawn(token);
+
+    unsafe { executor.poll() };
+}
+
+#[cfg(feature = "join-handle")]
+#[test]
+fn join_handle() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use embassy_sync::waitqueue::AtomicWaker;
+
+    /// Stand-in for a hardware timer, fired manually by the test.
+    struct Timer {
+        fired: AtomicBool,
+        waker: AtomicWaker,
+    }
+
+    impl Timer {
+        fn fire(&self) {
+            self.fired.store(true, Ordering::Relaxed);
+            self.waker.wake();
+        }
+
+        async fn wait(&self) {
+            poll_fn(|cx| {
+                self.waker.register(cx.waker());
+                match self.fired.load(Ordering::Relaxed) {
+                    true => Poll::Ready(()),
+                    false => Poll::Pending,
+                }
+            })
+            .await
+        }
+    }
+
+    #[task]
+    async fn child(trace: Trace, timer: &'static Timer) {
+        trace.push("child start");
+        timer.wait().await;
+        trace.push("child end");
+    }
+
+    #[task]
+    async fn parent(spawner: Spawner, trace: Trace, timer: &'static Timer) {
+        let handle = spawner.spawn_joinable(child(trace.clone(), timer).unwrap());
+        trace.push("parent wait");
+        handle.await;
+        trace.push("parent joined");
+
+        // The storage can be reused, and a detached handle doesn't get in the way.
+        drop(spawner.spawn_joinable(child(trace.clone(), timer).unwrap()));
+    }
+
+    let timer = Box::leak(Box::new(Timer {
+        fired: AtomicBool::new(false),
+        waker: AtomicWaker::new(),
+    }));
+
+    let (executor, trace) = setup();
+    executor
+        .spawner()
+        .spawn(parent(executor.spawner(), trace.clone(), timer).unwrap());
+
+    unsafe { executor.poll() };
+    unsafe { executor.poll() };
+    assert_eq!(trace.get(), &["pend", "pend", "parent wait", "child start"]);
+
+    timer.fire();
+    unsafe { executor.poll() };
+    unsafe { executor.poll() };
+    unsafe { executor.poll() };
+
+    assert_eq!(
+        trace.get(),
+        &[
+            "pend",          // spawning the parent pends the executor
+            "pend",          // spawning the child pends the executor
+            "parent wait",   //
+            "child start",   //
+            "pend",          // timer fires
+            "child end",     //
+            "pend",          // child exit wakes the parent
+            "parent joined", //
+            "pend",          // respawning the child pends the executor
+            "child start",   //
+            "child end",     // timer has already fired
+        ]
+    );
+}
+
+#[cfg(feature = "join-handle")]
+#[test]
+fn abort() {
+    use std::pin::Pin;
+    use std::task::{Context, Waker};
+
+    struct OnDrop(Trace);
+
+    impl Drop for OnDrop {
+        fn drop(&mut self) {
+            self.0.push("dropped");
+        }
+    }
+
+    #[task]
+    async fn looping(trace: Trace) {
+        let _guard = OnDrop(trace.clone());
+        loop {
+            trace.push("loop");
+            // Yield once.
+            let mut yielded = false;
+            poll_fn(|cx| {
+                if yielded {
+                    return Poll::Ready(());
+                }
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            })
+            .await;
+        }
+    }
+
+    let (executor, trace) = setup();
+    let mut handle = executor.spawner().spawn_joinable(looping(trace.clone()).unwrap());
+    let mut cx = Context::from_waker(Waker::noop());
+
+    unsafe { executor.poll() };
+    unsafe { executor.poll() };
+    assert_eq!(Pin::new(&mut handle).poll(&mut cx), Poll::Pending);
+
+    handle.abort();
+    unsafe { executor.poll() };
+    assert_eq!(trace.get(), &["pend", "loop", "pend", "loop", "pend", "dropped"]);
+    assert_eq!(Pin::new(&mut handle).poll(&mut cx), Poll::Ready(()));
+
+    // The pool slot is free again, and aborting a finished task does nothing.
+    let mut respawned = executor.spawner().spawn_joinable(looping(trace.clone()).unwrap());
+    handle.abort();
+    unsafe { executor.poll() };
+    assert_eq!(&trace.get()[6..], &["pend", "loop", "pend"]);
+    assert_eq!(Pin::new(&mut respawned).poll(&mut cx), Poll::Pending);
+}
+
+#[cfg(feature = "fairness-debug")]
+#[test]
+fn poll_budget() {
+    #[task]
+    async fn busy(trace: Trace) {
+        // Busy-waits on a flag that is never set.
+        loop {
+            poll_fn(|cx| {
+                cx.waker().wake_by_ref();
+                Poll::<()>::Pending
+            })
+            .await;
+            trace.push("busy");
+        }
+    }
+
+    #[task]
+    async fn well_behaved() {
+        // Yields once, then finishes.
+        let mut yielded = false;
+        poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    let (executor, _) = setup();
+    assert_eq!(executor.poll_budget(), embassy_executor::raw::DEFAULT_POLL_BUDGET);
+    executor.set_poll_budget(10);
+
+    for _ in 0..5 {
+        executor.spawner().spawn(well_behaved().unwrap());
+        unsafe { executor.poll() };
+        unsafe { executor.poll() };
+        unsafe { executor.poll() };
+    }
+    assert_eq!(executor.poll_budget_exceeded(), 0);
+
+    let (executor, trace) = setup();
+    executor.set_poll_budget(10);
+    executor.spawner().spawn(busy(trace.clone()).unwrap());
+    for _ in 0..9 {
+        unsafe { executor.poll() };
+    }
+    assert_eq!(executor.poll_budget_exceeded(), 0);
+    unsafe { executor.poll() };
+    assert_eq!(executor.poll_budget_exceeded(), 1);
+
+    // Warned only once per busy period.
+    for _ in 0..20 {
+        unsafe { executor.poll() };
+    }
+    assert_eq!(executor.poll_budget_exceeded(), 1);
+}
+
+#[test]
+fn move_task_between_executors() {
+    use embassy_executor::raw::{move_task, task_from_waker, TaskRef};
+    use embassy_sync::waitqueue::AtomicWaker;
+
+    struct Shared {
+        waker: AtomicWaker,
+        task: Mutex<Option<TaskRef>>,
+        polled_on: Mutex<Vec<usize>>,
+    }
+
+    #[task]
+    async fn task1(shared: &'static Shared) {
+        let mut polls = 0;
+        poll_fn(|cx| {
+            let task = task_from_waker(cx.waker());
+            let executor = unsafe { task.executor().unwrap() };
+            shared.polled_on.lock().unwrap().push(executor.id());
+            *shared.task.lock().unwrap() = Some(task);
+            shared.waker.register(cx.waker());
+
+            polls += 1;
+            if polls == 3 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    let shared = Box::leak(Box::new(Shared {
+        waker: AtomicWaker::new(),
+        task: Mutex::new(None),
+        polled_on: Mutex::new(Vec::new()),
+    }));
+
+    let (executor_a, trace_a) = setup();
+    let (executor_b, trace_b) = setup();
+    executor_a.spawner().spawn(task1(shared).unwrap());
+    unsafe { executor_a.poll() };
+
+    // The task is blocked, move it.
+    let task = shared.task.lock().unwrap().unwrap();
+    assert!(unsafe { move_task(task, executor_b) });
+    unsafe { executor_a.poll() };
+    unsafe { executor_b.poll() };
+
+    // Wakers registered before the move wake it in its new executor.
+    shared.waker.wake();
+    // It can't be moved while it's waiting in the run queue.
+    assert!(!unsafe { move_task(task, executor_a) });
+    unsafe { executor_a.poll() };
+    unsafe { executor_b.poll() };
+
+    assert_eq!(
+        *shared.polled_on.lock().unwrap(),
+        &[executor_a.id(), executor_b.id(), executor_b.id()]
+    );
+    // A finished task can't be moved.
+    assert!(!unsafe { move_task(task, executor_a) });
+    #[cfg(feature = "metrics")]
+    {
+        assert_eq!(executor_a.alive_task_count(), 0);
+        assert_eq!(executor_b.alive_task_count(), 0);
+    }
+    assert_eq!(trace_a.get(), &["pend"]);
+    assert_eq!(trace_b.get(), &["pend", "pend"]);
+}
+
+#[test]
+fn idle_hooks() {
+    use embassy_sync::waitqueue::AtomicWaker;
+
+    static EVENTS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    #[task]
+    async fn task1(trace: Trace, waker: &'static AtomicWaker) {
+        poll_fn(|cx| {
+            trace.push("poll task1");
+            waker.register(cx.waker());
+            Poll::<()>::Pending
+        })
+        .await
+    }
+
+    let (executor, trace) = setup();
+    let waker = Box::leak(Box::new(AtomicWaker::new()));
+    executor.spawner().spawn(task1(trace.clone(), waker).unwrap());
+
+    // Without hooks, idling only waits.
+    unsafe { executor.poll() };
+    executor.idle(|| trace.push("wait"));
+
+    executor.set_idle_hooks(
+        || EVENTS.lock().unwrap().push("idle"),
+        || EVENTS.lock().unwrap().push("wake"),
+    );
+
+    // Mimic the run loop of a thread-mode executor.
+    for _ in 0..2 {
+        executor.idle(|| {
+            EVENTS.lock().unwrap().push("wait");
+            waker.wake();
+        });
+        unsafe { executor.poll() };
+    }
+
+    assert_eq!(
+        trace.get(),
+        &["pend", "poll task1", "wait", "pend", "poll task1", "pend", "poll task1"]
+    );
+    assert_eq!(
+        *EVENTS.lock().unwrap(),
+        &["idle", "wait", "wake", "idle", "wait", "wake"]
+    );
+}
+
+#[test]
+fn generic_task() {
+    trait Peripheral: 'static {
+        const NAME: &'static str;
+    }
+
+    struct Uart1;
+    impl Peripheral for Uart1 {
+        const NAME: &'static str = "poll uart1";
+    }
+
+    struct Uart2;
+    impl Peripheral for Uart2 {
+        const NAME: &'static str = "poll uart2";
+    }
+
+    #[task(generics(Uart1, Uart2), slots)]
+    async fn driver<P: Peripheral>(trace: Trace, _p: P) {
+        trace.push(P::NAME)
+    }
+
+    let (executor, trace) = setup();
+    let spawner = executor.spawner();
+    spawner.spawn(driver(trace.clone(), Uart1).unwrap());
+    spawner.spawn(driver::<Uart2>(trace.clone(), Uart2).unwrap());
+
+    // Every concrete type has its own pool.
+    assert!(driver(trace.clone(), Uart1).is_err());
+    assert!(driver(trace.clone(), Uart2).is_err());
+    assert!(matches!(driver_in_slot(0, trace.clone(), Uart1), Err(SpawnError::Busy)));
+
+    unsafe { executor.poll() };
+    let mut polls = trace.get();
+    polls.sort();
+    assert_eq!(polls, &["pend", "poll uart1", "poll uart2"]);
+}
+
+#[test]
+fn stored_spawn_token() {
+    use embassy_executor::{Erased, SpawnToken};
+
+    #[task]
+    async fn task1(trace: Trace) {
+        trace.push("poll task1")
+    }
+
+    #[task]
+    async fn task2(trace: Trace) {
+        trace.push("poll task2")
+    }
+
+    enum Hardware {
+        Uart1,
+        Uart2,
+    }
+
+    // The tokens of the tasks for the detected hardware, to be spawned later. A token can't be
+    // dropped without spawning it, so only the tasks that will run get one.
+    struct Tasks {
+        tokens: Vec<SpawnToken<Erased>>,
+    }
+
+    impl Tasks {
+        fn new(detected: &[Hardware], trace: &Trace) -> Self {
+            let tokens = detected
+                .iter()
+                .map(|hardware| match hardware {
+                    Hardware::Uart1 => task1(trace.clone()).unwrap().erase(),
+                    Hardware::Uart2 => task2(trace.clone()).unwrap().erase(),
+                })
+                .collect();
+            Self { tokens }
+        }
+    }
+
+    for (detected, expected) in [
+        (
+            &[Hardware::Uart2, Hardware::Uart1][..],
+            &["pend", "poll task2", "pend", "poll task1"][..],
+        ),
+        (&[Hardware::Uart1][..], &["pend", "poll task1"][..]),
+    ] {
+        let (executor, trace) = setup();
+        let tasks = Tasks::new(detected, &trace);
+        for token in tasks.tokens {
+            executor.spawner().spawn(token);
+            unsafe { executor.poll() };
+        }
+        assert_eq!(trace.get(), expected);
+    }
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn task_counts() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use embassy_sync::waitqueue::AtomicWaker;
+
+    #[task(pool_size = 3)]
+    async fn waiter(waker: &'static AtomicWaker, done: &'static AtomicBool) {
+        poll_fn(|cx| {
+            waker.register(cx.waker());
+            match done.load(Ordering::Relaxed) {
+                true => Poll::Ready(()),
+                false => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    let (executor, _trace) = setup();
+    let spawner = executor.spawner();
+    let wakers: &'static [AtomicWaker; 3] = Box::leak(Box::new([const { AtomicWaker::new() }; 3]));
+    let done: &'static [AtomicBool; 3] = Box::leak(Box::new([const { AtomicBool::new(false) }; 3]));
+    assert_eq!((spawner.alive_task_count(), spawner.ready_task_count()), (0, 0));
+
+    for i in 0..3 {
+        spawner.spawn(waiter(&wakers[i], &done[i]).unwrap());
+    }
+    assert_eq!((executor.alive_task_count(), executor.ready_task_count()), (3, 3));
+
+    // All tasks are waiting.
+    unsafe { executor.poll() };
+    assert_eq!((executor.alive_task_count(), executor.ready_task_count()), (3, 0));
+
+    // One is woken, another one finishes.
+    wakers[0].wake();
+    done[1].store(true, Ordering::Relaxed);
+    wakers[1].wake();
+    assert_eq!((executor.alive_task_count(), executor.ready_task_count()), (3, 2));
+    unsafe { executor.poll() };
+    assert_eq!((executor.alive_task_count(), executor.ready_task_count()), (2, 0));
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn poll_hooks() {
+    static EVENTS: Mutex<Vec<(&'static str, u32)>> = Mutex::new(Vec::new());
+
+    #[task(pool_size = 2)]
+    async fn task1() {
+        let mut yielded = false;
+        poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    let (executor, _trace) = setup();
+    executor.set_poll_hooks(
+        |id| EVENTS.lock().unwrap().push(("before", id)),
+        |id| EVENTS.lock().unwrap().push(("after", id)),
+    );
+    executor.spawner().spawn(task1().unwrap());
+    executor.spawner().spawn(task1().unwrap());
+
+    // Poll both tasks twice, until they finish.
+    unsafe { executor.poll() };
+    unsafe { executor.poll() };
+
+    let events = EVENTS.lock().unwrap();
+    assert_eq!(events.len(), 8);
+    for pair in events.chunks(2) {
+        assert_eq!(pair[0].0, "before");
+        assert_eq!(pair[1].0, "after");
+        assert_eq!(pair[0].1, pair[1].1);
+    }
+
+    let mut ids: Vec<u32> = events.iter().map(|(_, id)| *id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 2);
+}
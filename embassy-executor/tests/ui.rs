@@ -4,6 +4,8 @@ fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/abi.rs");
     t.compile_fail("tests/ui/bad_return.rs");
+    t.compile_fail("tests/ui/main_bad_executor.rs");
+    t.compile_fail("tests/ui/main_bad_spawner.rs");
     t.compile_fail("tests/ui/generics.rs");
     t.compile_fail("tests/ui/impl_trait_nested.rs");
     t.compile_fail("tests/ui/impl_trait.rs");
@@ -33,6 +35,10 @@ fn ui() {
     t.compile_fail("tests/ui/type_error.rs");
     t.compile_fail("tests/ui/where_clause.rs");
     t.compile_fail("tests/ui/unsafe_op_in_unsafe_task.rs");
+    t.compile_fail("tests/ui/main_early_return_never.rs");
 
     t.pass("tests/ui/task_safety_attribute.rs");
+    t.pass("tests/ui/main_generic_executor.rs");
+    t.pass("tests/ui/main_result.rs");
+    t.pass("tests/ui/main_early_return.rs");
 }
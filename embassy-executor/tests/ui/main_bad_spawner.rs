@@ -0,0 +1,2 @@
+#[embassy_executor::main(executor = "some_crate::Executor")]
+async fn main(_x: u32) {}
@@ -0,0 +1,28 @@
+use embassy_executor::Spawner;
+
+struct Executor;
+
+impl Executor {
+    fn new() -> Self {
+        Self
+    }
+
+    fn run(&'static mut self, _init: impl FnOnce(Spawner)) -> ! {
+        std::process::exit(0)
+    }
+}
+
+#[export_name = "__pender"]
+fn pender(_: *mut ()) {}
+
+fn should_stop() -> bool {
+    true
+}
+
+#[embassy_executor::main(executor = "Executor")]
+async fn main(_spawner: Spawner) -> ! {
+    if should_stop() {
+        return;
+    }
+    loop {}
+}
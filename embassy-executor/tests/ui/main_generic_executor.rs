@@ -0,0 +1,29 @@
+use std::marker::PhantomData;
+
+use embassy_executor::Spawner;
+
+struct Config;
+
+struct Executor<C> {
+    _config: PhantomData<C>,
+}
+
+impl<C> Executor<C> {
+    fn new() -> Self {
+        Self { _config: PhantomData }
+    }
+
+    fn run(&'static mut self, _init: impl FnOnce(Spawner)) -> ! {
+        // The test only checks that the expansion compiles, so don't run the main task.
+        std::process::exit(0)
+    }
+}
+
+#[export_name = "__pender"]
+fn pender(_: *mut ()) {
+    // The test doesn't link if we don't include this.
+    // We never call this anyway.
+}
+
+#[embassy_executor::main(executor = "Executor<Config>")]
+async fn main(_spawner: Spawner) {}
@@ -0,0 +1,24 @@
+use cortex_m::interrupt::InterruptNumber;
+use embassy_executor::Spawner;
+
+#[derive(Clone, Copy)]
+enum Interrupt {
+    SWI0 = 0,
+}
+
+unsafe impl InterruptNumber for Interrupt {
+    fn number(self) -> u16 {
+        self as u16
+    }
+}
+
+// The expansion only runs on a Cortex-M core, so it is built in a module and never called.
+mod app {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[embassy_executor::main(entry = "", interrupt = "Interrupt::SWI0")]
+    async fn main(_spawner: Spawner) {}
+}
+
+fn main() {}
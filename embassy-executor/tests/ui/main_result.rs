@@ -0,0 +1,33 @@
+use embassy_executor::Spawner;
+
+struct Executor;
+
+impl Executor {
+    fn new() -> Self {
+        Self
+    }
+
+    fn run(&'static mut self, _init: impl FnOnce(Spawner)) -> ! {
+        // The test only checks that the expansion compiles, so don't run the main task.
+        std::process::exit(0)
+    }
+}
+
+#[export_name = "__pender"]
+fn pender(_: *mut ()) {
+    // The test doesn't link if we don't include this.
+    // We never call this anyway.
+}
+
+#[derive(Debug)]
+struct Error;
+
+fn fallible() -> Result<u32, Error> {
+    Err(Error)
+}
+
+#[embassy_executor::main(executor = "Executor")]
+async fn main(_spawner: Spawner) -> Result<(), Error> {
+    let _value = fallible()?;
+    Ok(())
+}
@@ -0,0 +1,14 @@
+// The thread-mode executor of `arch-cortex-m` uses Arm instructions, so only the interrupt
+// executor can be built on the host.
+#![cfg(all(
+    feature = "arch-cortex-m",
+    feature = "executor-interrupt",
+    not(feature = "executor-thread")
+))]
+
+#[cfg(not(miri))]
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/main_interrupt.rs");
+}
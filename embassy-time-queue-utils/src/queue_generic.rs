@@ -81,23 +81,32 @@ impl<const QUEUE_SIZE: usize> ConstGenericQueue<QUEUE_SIZE> {
     }
 
     /// Dequeues expired timers and returns the next alarm time.
+    ///
+    /// Expired timers are woken in the order they were first scheduled.
     pub fn next_expiration(&mut self, now: u64) -> u64 {
         let mut next_alarm = u64::MAX;
 
-        let mut i = 0;
-        while i < self.queue.len() {
-            let timer = &self.queue[i];
+        // `retain` keeps the order of the remaining timers, so that timers with the same expiration
+        // time are woken in the order they were scheduled.
+        self.queue.retain(|timer| {
             if timer.at <= now {
-                let timer = self.queue.swap_remove(i);
-                timer.waker.wake();
+                timer.waker.wake_by_ref();
+                false
             } else {
                 next_alarm = min(next_alarm, timer.at);
-                i += 1;
+                true
             }
-        }
+        });
 
         next_alarm
     }
+
+    /// Returns the expiration times of the timers in the queue, in no particular order.
+    ///
+    /// This includes expired timers that haven't been dequeued by [`ConstGenericQueue::next_expiration`] yet.
+    pub fn deadlines(&self) -> impl Iterator<Item = u64> + '_ {
+        self.queue.iter().map(|timer| timer.at)
+    }
 }
 
 #[cfg(feature = "generic-queue-8")]
@@ -142,7 +151,16 @@ impl Queue {
     }
 
     /// Dequeues expired timers and returns the next alarm time.
+    ///
+    /// Expired timers are woken in the order they were first scheduled.
     pub fn next_expiration(&mut self, now: u64) -> u64 {
         self.queue.next_expiration(now)
     }
+
+    /// Returns the expiration times of the timers in the queue, in no particular order.
+    ///
+    /// This includes expired timers that haven't been dequeued by [`Queue::next_expiration`] yet.
+    pub fn deadlines(&self) -> impl Iterator<Item = u64> + '_ {
+        self.queue.deadlines()
+    }
 }
@@ -34,6 +34,8 @@ unsafe impl Sync for QueueItem {}
 /// is not attempted to be enqueued in multiple queues.
 pub struct Queue {
     head: Cell<Option<NonNull<QueueItem>>>,
+    /// The last item in the queue, where new items are appended.
+    tail: Cell<Option<NonNull<QueueItem>>>,
 }
 
 impl core::fmt::Debug for Queue {
@@ -48,7 +50,10 @@ unsafe impl Sync for Queue {}
 impl Queue {
     /// Creates a new timer queue.
     pub const fn new() -> Self {
-        Self { head: Cell::new(None) }
+        Self {
+            head: Cell::new(None),
+            tail: Cell::new(None),
+        }
     }
 
     /// Schedules a task to run at a specific time.
@@ -78,17 +83,21 @@ impl Queue {
             }
             None => {
                 // If not in the queue, add it and update.
-                let mut item_ptr = NonNull::from(item);
-                let prev = self.head.replace(Some(item_ptr));
-
-                let item = unsafe { item_ptr.as_mut() };
+                let item_ptr = NonNull::from(&mut *item);
 
                 item.expires_at = at;
                 item.waker = Some(waker.clone());
-                item.next.set(prev);
+                item.next.set(None);
                 // The default implementation doesn't care about the
                 // opaque payload, leave it unchanged.
 
+                // Append the item, so that items with the same expiration time are woken in the
+                // order they were scheduled.
+                match self.tail.replace(Some(item_ptr)) {
+                    Some(tail) => unsafe { tail.as_ref() }.next.set(Some(item_ptr)),
+                    None => self.head.set(Some(item_ptr)),
+                }
+
                 true
             }
         }
@@ -96,6 +105,8 @@ impl Queue {
 
     /// Dequeues expired timers and returns the next alarm time.
     ///
+    /// Expired timers are woken in the order they were first scheduled.
+    ///
     /// The provided callback will be called for each expired task. Tasks that never expire
     /// will be removed, but the callback will not be called.
     pub fn next_expiration(&mut self, now: u64) -> u64 {
@@ -118,13 +129,29 @@ impl Queue {
         next_expiration
     }
 
+    /// Returns the expiration times of the items in the queue, in no particular order.
+    ///
+    /// This includes expired items that haven't been dequeued by [`Queue::next_expiration`] yet.
+    pub fn deadlines(&self) -> impl Iterator<Item = u64> + '_ {
+        let mut next = self.head.get();
+        core::iter::from_fn(move || {
+            // Safety: items are only unlinked through `&mut self`, so they stay valid while the
+            // queue is borrowed.
+            let item = unsafe { next?.as_ref() };
+            next = item.next.get();
+            Some(item.expires_at)
+        })
+    }
+
     fn retain(&mut self, mut f: impl FnMut(&mut QueueItem) -> bool) {
         let mut prev = &self.head;
+        let mut last = None;
         while let Some(mut p) = prev.get() {
             let mut item = unsafe { p.as_mut() };
 
             if f(&mut item) {
                 // Skip to next
+                last = Some(p);
                 prev = &item.next;
             } else {
                 // Remove it
@@ -132,5 +159,6 @@ impl Queue {
                 item.next.set(None);
             }
         }
+        self.tail.set(last);
     }
 }
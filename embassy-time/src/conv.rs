@@ -0,0 +1,201 @@
+//! Conversions between ticks and time units, for a given tick rate.
+//!
+//! These are the conversions [`Duration`](crate::Duration) uses, with the tick rate as an
+//! argument instead of [`TICK_HZ`](crate::TICK_HZ). They are meant for time drivers and other
+//! code that deals with raw tick counts, so that it rounds exactly like `Duration` does.
+//!
+//! Conversions from ticks round down, like [`Duration::as_millis`](crate::Duration::as_millis),
+//! and conversions to ticks round up, like [`Duration::from_millis`](crate::Duration::from_millis).
+//!
+//! [`secs_to_ticks`] and [`secs_and_nanos_to_ticks`] saturate to `u64::MAX`, like
+//! [`Duration::from_secs`](crate::Duration::from_secs). [`ticks_to_secs`] and
+//! [`ticks_to_secs_and_nanos`] can't overflow. The conversions between ticks and milliseconds,
+//! microseconds or nanoseconds overflow for large values, which panics in debug builds, like the
+//! corresponding `Duration` methods.
+//!
+//! `hz` must not be zero. Conversions from ticks panic with a division by zero if it is, and
+//! conversions to ticks return zero.
+
+/// Greatest common divisor of `a` and `b`.
+pub const fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Convert a tick count at `hz` ticks per second to seconds, rounding down.
+pub const fn ticks_to_secs(ticks: u64, hz: u64) -> u64 {
+    ticks / hz
+}
+
+/// Convert a tick count at `hz` ticks per second to milliseconds, rounding down.
+pub const fn ticks_to_millis(ticks: u64, hz: u64) -> u64 {
+    convert(ticks, hz, 1000, gcd(hz, 1000))
+}
+
+/// Convert a tick count at `hz` ticks per second to microseconds, rounding down.
+pub const fn ticks_to_micros(ticks: u64, hz: u64) -> u64 {
+    convert(ticks, hz, 1_000_000, gcd(hz, 1_000_000))
+}
+
+/// Convert a tick count at `hz` ticks per second to nanoseconds, rounding down.
+pub const fn ticks_to_nanos(ticks: u64, hz: u64) -> u64 {
+    convert(ticks, hz, 1_000_000_000, gcd(hz, 1_000_000_000))
+}
+
+/// Split a tick count at `hz` ticks per second into whole seconds and the remaining nanoseconds,
+/// rounding down. This can't overflow.
+pub const fn ticks_to_secs_and_nanos(ticks: u64, hz: u64) -> (u64, u32) {
+    let nanos = (ticks % hz) as u128 * 1_000_000_000 / hz as u128;
+    (ticks / hz, nanos as u32)
+}
+
+/// Convert seconds to a tick count at `hz` ticks per second, saturating to `u64::MAX`.
+pub const fn secs_to_ticks(secs: u64, hz: u64) -> u64 {
+    secs.saturating_mul(hz)
+}
+
+/// Convert milliseconds to a tick count at `hz` ticks per second, rounding up.
+pub const fn millis_to_ticks(millis: u64, hz: u64) -> u64 {
+    convert_ceil(millis, 1000, hz, gcd(hz, 1000))
+}
+
+/// Convert microseconds to a tick count at `hz` ticks per second, rounding up.
+pub const fn micros_to_ticks(micros: u64, hz: u64) -> u64 {
+    convert_ceil(micros, 1_000_000, hz, gcd(hz, 1_000_000))
+}
+
+/// Convert nanoseconds to a tick count at `hz` ticks per second, rounding up.
+pub const fn nanos_to_ticks(nanos: u64, hz: u64) -> u64 {
+    convert_ceil(nanos, 1_000_000_000, hz, gcd(hz, 1_000_000_000))
+}
+
+/// Convert whole seconds and nanoseconds to a tick count at `hz` ticks per second, rounding up and
+/// saturating to `u64::MAX`. `nanos` may be a second or more.
+pub const fn secs_and_nanos_to_ticks(secs: u64, nanos: u32, hz: u64) -> u64 {
+    secs_to_ticks(secs, hz).saturating_add(nanos_to_ticks(nanos as u64, hz))
+}
+
+/// Convert `value` from a rate of `from` to a rate of `to` per second, rounding down.
+///
+/// `gcd` must be `gcd(from, to)`. It is a parameter so that it can be precomputed for `TICK_HZ`.
+#[inline]
+pub(crate) const fn convert(value: u64, from: u64, to: u64, gcd: u64) -> u64 {
+    value * (to / gcd) / (from / gcd)
+}
+
+/// Like [`convert`], but rounding up.
+#[inline]
+pub(crate) const fn convert_ceil(value: u64, from: u64, to: u64, gcd: u64) -> u64 {
+    div_ceil(value * (to / gcd), from / gcd)
+}
+
+#[inline]
+pub(crate) const fn div_ceil(num: u64, den: u64) -> u64 {
+    num.div_ceil(den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Duration, TICK_HZ};
+
+    #[test]
+    fn test_matches_duration() {
+        for value in [0, 1, 7, 999, 1000, 123_456_789] {
+            let ticks = Duration::from_ticks(value);
+            assert_eq!(ticks_to_secs(value, TICK_HZ), ticks.as_secs());
+            assert_eq!(ticks_to_millis(value, TICK_HZ), ticks.as_millis());
+            assert_eq!(ticks_to_micros(value, TICK_HZ), ticks.as_micros());
+            assert_eq!(ticks_to_nanos(value, TICK_HZ), ticks.as_nanos());
+
+            assert_eq!(secs_to_ticks(value, TICK_HZ), Duration::from_secs(value).as_ticks());
+            assert_eq!(millis_to_ticks(value, TICK_HZ), Duration::from_millis(value).as_ticks());
+            assert_eq!(micros_to_ticks(value, TICK_HZ), Duration::from_micros(value).as_ticks());
+            assert_eq!(nanos_to_ticks(value, TICK_HZ), Duration::from_nanos(value).as_ticks());
+
+            assert_eq!(ticks_to_secs_and_nanos(value, TICK_HZ), ticks.as_secs_and_nanos());
+            assert_eq!(
+                secs_and_nanos_to_ticks(value, value as u32, TICK_HZ),
+                Duration::from_secs_and_nanos(value, value as u32).as_ticks()
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_tick_rate() {
+        assert_eq!(ticks_to_millis(32_768, 32_768), 1000);
+        assert_eq!(ticks_to_millis(32, 32_768), 0);
+        assert_eq!(millis_to_ticks(1, 32_768), 33);
+        assert_eq!(micros_to_ticks(1, 32_768), 1);
+        assert_eq!(ticks_to_micros(1, 32_768), 30);
+        assert_eq!(secs_to_ticks(u64::MAX, 32_768), u64::MAX);
+        assert_eq!(gcd(32_768, 1_000_000), 64);
+    }
+
+    #[test]
+    fn test_nanos_round_trip() {
+        for hz in [32_768, 1_000_000, 1_000_000_000, 3_000_000_000] {
+            let tick_nanos = 1_000_000_000u64.div_ceil(hz);
+            for nanos in [0, 1, 999, 1_000, 123_456_789, 1 << 40] {
+                let back = ticks_to_nanos(nanos_to_ticks(nanos, hz), hz);
+                assert!((nanos..nanos + tick_nanos).contains(&back), "{nanos}ns at {hz}Hz");
+            }
+        }
+
+        // At 1GHz, ticks are nanoseconds, so the whole range converts without overflowing.
+        assert_eq!(ticks_to_nanos(u64::MAX, 1_000_000_000), u64::MAX);
+        assert_eq!(nanos_to_ticks(u64::MAX, 1_000_000_000), u64::MAX);
+    }
+
+    #[test]
+    fn test_secs_and_nanos() {
+        // At 32768Hz, a tick is 30517.578125ns, so the nanoseconds are rounded down.
+        assert_eq!(ticks_to_secs_and_nanos(32_768 * 3 + 1, 32_768), (3, 30_517));
+        assert_eq!(ticks_to_secs_and_nanos(32_768 - 1, 32_768), (0, 999_969_482));
+        assert_eq!(secs_and_nanos_to_ticks(3, 30_517, 32_768), 32_768 * 3 + 1);
+        assert_eq!(secs_and_nanos_to_ticks(0, 1, 32_768), 1);
+        assert_eq!(secs_and_nanos_to_ticks(1, 2_000_000_000, 32_768), 32_768 * 3);
+        assert_eq!(
+            secs_and_nanos_to_ticks(u64::MAX / 32_768, 999_999_999, 32_768),
+            u64::MAX
+        );
+        assert_eq!(
+            ticks_to_secs_and_nanos(3_000_000_000 * 5 + 1_000_000_000, 3_000_000_000),
+            (5, 333_333_333)
+        );
+        assert_eq!(
+            ticks_to_secs_and_nanos(u64::MAX, 1_000_000_000),
+            (u64::MAX / 1_000_000_000, 709_551_615)
+        );
+
+        // Rounding down and then up gives back the same tick count, as long as a tick is at
+        // least a nanosecond.
+        for hz in [32_768, 1_000_000, 1_000_000_000] {
+            for ticks in [0, 1, 7, 32_767, 32_768, 123_456_789, u64::MAX] {
+                let (secs, nanos) = ticks_to_secs_and_nanos(ticks, hz);
+                assert!(nanos < 1_000_000_000);
+                assert_eq!(
+                    secs_and_nanos_to_ticks(secs, nanos, hz),
+                    ticks,
+                    "{ticks} ticks at {hz}Hz"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_hz() {
+        assert_eq!(secs_to_ticks(1, 0), 0);
+        assert_eq!(millis_to_ticks(1, 0), 0);
+        assert_eq!(secs_and_nanos_to_ticks(1, 1, 0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_zero_hz_from_ticks() {
+        ticks_to_millis(1, 0);
+    }
+}
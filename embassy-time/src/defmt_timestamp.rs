@@ -0,0 +1,80 @@
+//! defmt timestamps with a unit selected at runtime, enabled by the `defmt-timestamp-uptime-runtime` feature.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Instant;
+
+#[cfg(any(
+    feature = "defmt-timestamp-uptime",
+    feature = "defmt-timestamp-uptime-s",
+    feature = "defmt-timestamp-uptime-ms",
+    feature = "defmt-timestamp-uptime-us",
+    feature = "defmt-timestamp-uptime-ts",
+    feature = "defmt-timestamp-uptime-tms",
+    feature = "defmt-timestamp-uptime-tus",
+))]
+compile_error!(
+    "`defmt-timestamp-uptime-runtime` can't be combined with the other `defmt-timestamp-uptime-*` features."
+);
+
+/// Unit of the defmt timestamps, see [`set_defmt_timestamp_unit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+#[repr(u8)]
+pub enum DefmtTimestampUnit {
+    /// Uptime in seconds, like `defmt-timestamp-uptime-s`.
+    Secs,
+    /// Uptime in milliseconds, like `defmt-timestamp-uptime-ms`.
+    Millis,
+    /// Uptime in microseconds, like `defmt-timestamp-uptime-us`.
+    Micros,
+    /// Uptime in seconds, displayed as time, like `defmt-timestamp-uptime-ts`.
+    TimeSecs,
+    /// Uptime in milliseconds, displayed as time, like `defmt-timestamp-uptime-tms`.
+    TimeMillis,
+    /// Uptime in microseconds, displayed as time, like `defmt-timestamp-uptime-tus`.
+    TimeMicros,
+}
+
+impl DefmtTimestampUnit {
+    const ALL: [Self; 6] = [
+        Self::Secs,
+        Self::Millis,
+        Self::Micros,
+        Self::TimeSecs,
+        Self::TimeMillis,
+        Self::TimeMicros,
+    ];
+}
+
+static UNIT: AtomicU8 = AtomicU8::new(DefmtTimestampUnit::Micros as u8);
+
+/// Sets the unit of the timestamps displayed next to defmt log messages.
+///
+/// This can be called at any time, for example after reading a boot-time configuration.
+/// Messages logged before the first call use microseconds.
+pub fn set_defmt_timestamp_unit(unit: DefmtTimestampUnit) {
+    UNIT.store(unit as u8, Ordering::Relaxed);
+}
+
+/// Returns the unit of the timestamps displayed next to defmt log messages.
+pub fn defmt_timestamp_unit() -> DefmtTimestampUnit {
+    DefmtTimestampUnit::ALL[UNIT.load(Ordering::Relaxed) as usize]
+}
+
+struct Timestamp;
+
+impl defmt::Format for Timestamp {
+    fn format(&self, f: defmt::Formatter) {
+        let now = Instant::now();
+        match defmt_timestamp_unit() {
+            DefmtTimestampUnit::Secs => defmt::write!(f, "{=u64}", now.as_secs()),
+            DefmtTimestampUnit::Millis => defmt::write!(f, "{=u64:ms}", now.as_millis()),
+            DefmtTimestampUnit::Micros => defmt::write!(f, "{=u64:us}", now.as_micros()),
+            DefmtTimestampUnit::TimeSecs => defmt::write!(f, "{=u64:ts}", now.as_secs()),
+            DefmtTimestampUnit::TimeMillis => defmt::write!(f, "{=u64:tms}", now.as_millis()),
+            DefmtTimestampUnit::TimeMicros => defmt::write!(f, "{=u64:tus}", now.as_micros()),
+        }
+    }
+}
+
+defmt::timestamp! {"{}", Timestamp}
@@ -1,12 +1,34 @@
 use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
 
 use super::{Duration, Instant};
 use crate::Timer;
 
 /// Blocks for at least `duration`.
+///
+/// This busy-waits, keeping the core running at full power for the whole delay. A spin-loop hint
+/// is issued between checks of the time, which lets the CPU save some power or yield to a sibling
+/// hardware thread where supported, but doesn't put it to sleep. Prefer [`Timer`] when
+/// running in an async context.
 pub fn block_for(duration: Duration) {
+    block_while(duration, || {})
+}
+
+/// Blocks for at least `duration`, calling `f` repeatedly while waiting.
+///
+/// This can be used to do some work while busy-waiting, like feeding a watchdog or polling a flag.
+/// `f` is called at least once. It must be cheap and must not block, as the delay can't end while
+/// it's running.
+pub fn block_while(duration: Duration, mut f: impl FnMut()) {
     let expires_at = Instant::now() + duration;
-    while Instant::now() < expires_at {}
+    loop {
+        f();
+        if Instant::now() >= expires_at {
+            break;
+        }
+        core::hint::spin_loop();
+    }
 }
 
 /// Type implementing async delays and blocking `embedded-hal` delays.
@@ -19,6 +41,86 @@ pub fn block_for(duration: Duration) {
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Delay;
 
+impl Delay {
+    /// Starts an async delay of `duration` that can be cancelled or rescheduled while it's pending.
+    ///
+    /// ``` no_run
+    /// use embassy_time::{Delay, Duration};
+    ///
+    /// # async fn demo() {
+    /// let mut delay = Delay::start(Duration::from_secs(5));
+    /// // ...
+    /// delay.reschedule(Duration::from_secs(10));
+    /// (&mut delay).await;
+    /// # }
+    /// ```
+    pub fn start(duration: Duration) -> DelayGuard {
+        DelayGuard {
+            timer: Timer::after(duration),
+            cancelled: false,
+            waker: None,
+        }
+    }
+}
+
+/// An async delay that can be cancelled or rescheduled, returned by [`Delay::start()`].
+///
+/// The guard remembers the waker of the task that last polled it. [`cancel()`](Self::cancel) and
+/// [`reschedule()`](Self::reschedule) wake that task, so that it polls the guard again and sees
+/// the change, even if it is waiting on other futures as well, for example in a `select`.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct DelayGuard {
+    timer: Timer,
+    cancelled: bool,
+    waker: Option<Waker>,
+}
+
+impl DelayGuard {
+    /// Cancels the delay, which completes the next time it is polled.
+    ///
+    /// This takes `&mut self` rather than `self`, so that a delay awaited by reference
+    /// (`(&mut guard).await`) can be cut short without dropping it.
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+        self.wake();
+    }
+
+    /// Re-arms the delay to complete after `duration`, measured from now.
+    ///
+    /// This also undoes a previous [`cancel()`](Self::cancel).
+    pub fn reschedule(&mut self, duration: Duration) {
+        self.timer = Timer::after(duration);
+        self.cancelled = false;
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Unpin for DelayGuard {}
+
+impl Future for DelayGuard {
+    type Output = ();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.cancelled {
+            return Poll::Ready(());
+        }
+        let poll = Pin::new(&mut self.timer).poll(cx);
+        if poll.is_pending() {
+            match &mut self.waker {
+                Some(w) if w.will_wake(cx.waker()) => {}
+                w => *w = Some(cx.waker().clone()),
+            }
+        }
+        poll
+    }
+}
+
 impl embedded_hal_1::delay::DelayNs for Delay {
     fn delay_ns(&mut self, ns: u32) {
         block_for(Duration::from_nanos(ns as u64))
@@ -82,3 +184,130 @@ impl embedded_hal_02::blocking::delay::DelayUs<u32> for Delay {
         block_for(Duration::from_micros(us as u64))
     }
 }
+
+#[cfg(all(test, feature = "mock-driver"))]
+mod tests {
+    use core::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::MockDriver;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_block_while() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let mut calls = 0;
+        block_while(Duration::from_millis(10), || {
+            calls += 1;
+            driver.advance(Duration::from_millis(1));
+        });
+        assert_eq!(calls, 10);
+        assert_eq!(Instant::now(), Instant::from_millis(10));
+
+        let mut calls = 0;
+        block_while(Duration::ZERO, || calls += 1);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_block_for() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let delay = std::thread::spawn(|| {
+            let start = Instant::now();
+            block_for(Duration::from_millis(10));
+            Instant::now() - start
+        });
+
+        // The delay only returns once the mock time has advanced far enough.
+        while !delay.is_finished() {
+            driver.advance(Duration::from_millis(1));
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(delay.join().unwrap() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    #[serial]
+    fn test_blocking_delay_ns() {
+        use embedded_hal_1::delay::DelayNs;
+
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let delay = std::thread::spawn(|| {
+            let start = Instant::now();
+            Delay.delay_ms(5);
+            Instant::now() - start
+        });
+
+        // The delay blocks until the mock time has advanced far enough.
+        while !delay.is_finished() {
+            driver.advance(Duration::from_millis(1));
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(delay.join().unwrap() >= Duration::from_millis(5));
+    }
+
+    #[test]
+    #[serial]
+    fn test_delay_guard_cancel() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut delay = Delay::start(Duration::from_secs(1));
+        assert_eq!(Pin::new(&mut delay).poll(&mut cx), Poll::Pending);
+
+        driver.advance(Duration::from_millis(500));
+        delay.cancel();
+        // The polling task is woken to observe the cancellation.
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert_eq!(Pin::new(&mut delay).poll(&mut cx), Poll::Ready(()));
+        assert_eq!(Instant::now(), Instant::from_millis(500));
+    }
+
+    #[test]
+    #[serial]
+    fn test_delay_guard_reschedule() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut delay = pin!(Delay::start(Duration::from_secs(1)));
+        assert_eq!(delay.as_mut().poll(&mut cx), Poll::Pending);
+
+        driver.advance(Duration::from_millis(800));
+        delay.reschedule(Duration::from_secs(1));
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert_eq!(delay.as_mut().poll(&mut cx), Poll::Pending);
+
+        // The original deadline has passed, but the delay was re-armed.
+        driver.advance(Duration::from_millis(999));
+        assert_eq!(delay.as_mut().poll(&mut cx), Poll::Pending);
+        driver.advance(Duration::from_millis(1));
+        assert_eq!(delay.as_mut().poll(&mut cx), Poll::Ready(()));
+        assert_eq!(Instant::now(), Instant::from_millis(1800));
+    }
+}
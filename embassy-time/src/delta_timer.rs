@@ -0,0 +1,74 @@
+use crate::{Duration, Instant};
+
+/// Measures the time between successive calls, for loops that need the time step between iterations.
+///
+/// ``` no_run
+/// use embassy_time::{DeltaTimer, Timer};
+///
+/// # async fn example() {
+/// let mut dt = DeltaTimer::new();
+/// loop {
+///     let elapsed = dt.tick();
+///     // integrate sensor readings over `elapsed`...
+///     Timer::after_millis(10).await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DeltaTimer {
+    last: Option<Instant>,
+}
+
+impl DeltaTimer {
+    /// Create a new `DeltaTimer`. The first call to [`tick()`](Self::tick) returns zero.
+    pub const fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// Returns the time elapsed since the previous call, or zero on the first call.
+    pub fn tick(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = match self.last {
+            Some(last) => now.saturating_duration_since(last),
+            None => Duration::ZERO,
+        };
+        self.last = Some(now);
+        elapsed
+    }
+
+    /// Forget the previous call, so that the next call to [`tick()`](Self::tick) returns zero.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+#[cfg(all(test, feature = "mock-driver"))]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::MockDriver;
+
+    #[test]
+    #[serial]
+    fn test_delta_timer() {
+        let driver = MockDriver::get();
+        driver.reset();
+        driver.advance(Duration::from_secs(1));
+
+        let mut dt = DeltaTimer::new();
+        assert_eq!(dt.tick(), Duration::ZERO);
+
+        for ms in [10, 25, 0, 1000, 3] {
+            driver.advance(Duration::from_millis(ms));
+            assert_eq!(dt.tick(), Duration::from_millis(ms));
+        }
+
+        driver.advance(Duration::from_millis(50));
+        dt.reset();
+        assert_eq!(dt.tick(), Duration::ZERO);
+        driver.advance(Duration::from_millis(7));
+        assert_eq!(dt.tick(), Duration::from_millis(7));
+    }
+}
@@ -1,5 +1,8 @@
 use core::cell::RefCell;
-use core::task::Waker;
+use core::future::Future;
+use core::pin::pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
 use critical_section::Mutex as CsMutex;
 use embassy_time_driver::Driver;
@@ -13,6 +16,9 @@ use crate::{Duration, Instant};
 /// This driver can also be used to test runtime functionality, such as
 /// timers, delays, etc.
 ///
+/// Due alarms are fired in order of their deadline, and alarms with the same deadline are fired
+/// in the order they were scheduled, so tests can rely on the order tasks are woken in.
+///
 /// # Example
 ///
 /// ```ignore
@@ -46,21 +52,238 @@ impl MockDriver {
 
     /// Resets the internal state of the mock driver
     /// This will clear and deallocate all alarms, and reset the current time to 0.
+    ///
+    /// Every setting goes back to its default as well: the driver is initialized, and auto-advance
+    /// is disabled. The wakers of the cleared alarms are dropped without being woken, so tasks
+    /// still waiting on a timer from before the reset never complete.
+    ///
+    /// There is a single mock driver, shared by the whole test binary, as [`Instant::now`] has no
+    /// way to tell tests apart. Tests using it must call this first, and must not run in parallel,
+    /// for example by marking them with `#[serial]` from the `serial_test` crate.
     pub fn reset(&self) {
         critical_section::with(|cs| {
             self.0.borrow(cs).replace(InnerMockDriver::new());
         });
     }
 
+    /// Sets whether the mock driver reports itself as initialized.
+    ///
+    /// While uninitialized, [`Instant::try_now`] returns `None`. This is useful for
+    /// testing code that may run before the time driver is set up.
+    /// The driver is initialized after [`MockDriver::reset`].
+    pub fn set_initialized(&self, initialized: bool) {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).initialized = initialized);
+    }
+
+    /// Returns the current time in ticks, or `None` if the driver is set as uninitialized.
+    pub(crate) fn try_now(&self) -> Option<u64> {
+        critical_section::with(|cs| {
+            let inner = self.0.borrow_ref(cs);
+            inner.initialized.then(|| inner.now.as_ticks())
+        })
+    }
+
+    /// Sets whether the mock driver advances time by itself when the executor goes idle.
+    ///
+    /// In auto-advance mode, [`MockDriver::on_idle`] jumps to the soonest pending alarm, so timers
+    /// complete immediately instead of waiting for the test to advance the time. Auto-advance is
+    /// disabled after [`MockDriver::reset`].
+    pub fn set_auto_advance(&self, enabled: bool) {
+        critical_section::with(|cs| self.0.borrow_ref_mut(cs).auto_advance = enabled);
+    }
+
+    /// Idle hook, to be called by an executor when it has no ready work and is about to sleep.
+    ///
+    /// In auto-advance mode, this advances the time to the soonest pending alarm, firing it, and
+    /// returns `true`: the executor should poll again instead of sleeping. Otherwise, or if there
+    /// are no pending alarms, the time is left unchanged and this returns `false`.
+    ///
+    /// [`MockDriver::block_on`] is an executor that calls this hook. To use auto-advance with another
+    /// executor, call this hook right before it waits for a wakeup. With `embassy-executor`, install
+    /// it as the executor's idle hook:
+    ///
+    /// ```ignore
+    /// let executor = Executor::new();
+    /// executor.set_idle_hooks(|| { MockDriver::get().on_idle(); }, || {});
+    /// MockDriver::get().set_auto_advance(true);
+    /// ```
+    ///
+    /// The alarm fires from the hook, which wakes the task waiting on it, so the executor resumes
+    /// right away instead of sleeping.
+    pub fn on_idle(&self) -> bool {
+        critical_section::with(|cs| self.0.borrow_ref(cs).auto_advance) && self.advance_to_next_alarm().is_some()
+    }
+
+    /// Runs a future to completion on the current thread.
+    ///
+    /// The future is polled whenever it is woken. When it is pending and hasn't been woken,
+    /// [`MockDriver::on_idle`] is called, so with auto-advance enabled timers complete immediately:
+    ///
+    /// ```ignore
+    /// let driver = embassy_time::MockDriver::get();
+    /// driver.set_auto_advance(true);
+    /// driver.block_on(Timer::after_secs(3600));
+    /// assert_eq!(Instant::now(), Instant::from_secs(3600));
+    /// ```
+    ///
+    /// Without auto-advance, this busy-waits for a wakeup, for example from another thread
+    /// calling [`MockDriver::advance`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if auto-advance is enabled and the future is pending with no pending alarms and
+    /// no wakeup, as it would never complete.
+    pub fn block_on<F: Future>(&self, fut: F) -> F::Output {
+        // Wakers may outlive this call, for example in the alarm queue, so they can't point to a
+        // local. Instead, every call gets its own number, which its wakers carry: wakers left over
+        // from earlier calls, possibly from other tests, don't wake this one.
+        static CURRENT: AtomicUsize = AtomicUsize::new(0);
+        static WOKEN: AtomicUsize = AtomicUsize::new(0);
+        fn wake(data: *const ()) {
+            if data as usize == CURRENT.load(Ordering::Acquire) {
+                WOKEN.store(data as usize, Ordering::Release);
+            }
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(|data| RawWaker::new(data, &VTABLE), wake, wake, |_| {});
+
+        let id = CURRENT.load(Ordering::Relaxed).wrapping_add(1).max(1);
+        CURRENT.store(id, Ordering::Release);
+
+        // SAFETY: the data pointer is only used as a number.
+        let waker = unsafe { Waker::from_raw(RawWaker::new(id as *const (), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut fut = pin!(fut);
+        loop {
+            WOKEN.store(0, Ordering::Release);
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+            while WOKEN.load(Ordering::Acquire) != id && !self.on_idle() {
+                let auto_advance = critical_section::with(|cs| self.0.borrow_ref(cs).auto_advance);
+                assert!(
+                    !auto_advance,
+                    "future is pending with no pending alarms, it will never complete"
+                );
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+    /// Returns the current time of the mock driver as an [`Instant`].
+    ///
+    /// This is the same value as [`Driver::now`], without the conversion from ticks.
+    pub fn now_instant(&self) -> Instant {
+        critical_section::with(|cs| self.0.borrow_ref(cs).now)
+    }
+
+    /// Returns the deadline of the soonest pending alarm, if any.
+    ///
+    /// This only reads the alarm queue. Alarms are fired by [`MockDriver::advance`], and when they
+    /// are scheduled with a deadline that has already passed, so the returned deadline is always
+    /// in the future.
+    pub fn next_alarm(&self) -> Option<Instant> {
+        critical_section::with(|cs| {
+            let inner = self.0.borrow_ref(cs);
+            // `u64::MAX` marks entries that are only waiting to be dequeued.
+            let next = inner.queue.deadlines().filter(|&at| at != u64::MAX).min();
+            next.map(Instant::from_ticks)
+        })
+    }
+
+    /// Asserts that no alarms are pending, for example at the end of a test.
+    ///
+    /// A pending alarm usually means a [`Timer`](crate::Timer) or [`Ticker`](crate::Ticker) was
+    /// dropped before it completed, or that a task is still waiting on one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any alarm is pending, listing the deadlines of the first few, soonest first.
+    pub fn assert_no_pending_alarms(&self) {
+        const MAX_LISTED: usize = 8;
+
+        let (now, count, mut listed) = critical_section::with(|cs| {
+            let inner = &mut *self.0.borrow_ref_mut(cs);
+            // Dequeue the due alarms, so only pending ones are left.
+            inner.queue.next_expiration(inner.now.as_ticks());
+            let mut listed = [u64::MAX; MAX_LISTED];
+            let mut count = 0;
+            for at in inner.queue.deadlines().filter(|&at| at != u64::MAX) {
+                if let Some(slot) = listed.iter_mut().max() {
+                    *slot = (*slot).min(at);
+                }
+                count += 1;
+            }
+            (inner.now, count, listed)
+        });
+
+        if count == 0 {
+            return;
+        }
+        listed.sort_unstable();
+        // The deadline list only implements `Display`, so this always uses the core macro.
+        ::core::panic!(
+            "{} alarm(s) still pending at {}, due at {}{}",
+            count,
+            now,
+            DeadlineList(&listed[..count.min(MAX_LISTED)]),
+            if count > MAX_LISTED { ", ..." } else { "" }
+        );
+    }
+
+    /// Advances the time exactly to the soonest pending alarm, firing it.
+    ///
+    /// Returns the deadline of the alarm, or `None` if there are no pending alarms,
+    /// in which case the time is left unchanged.
+    pub fn advance_to_next_alarm(&self) -> Option<Instant> {
+        let at = self.next_alarm()?;
+        self.advance(at - self.now_instant());
+        Some(at)
+    }
+
+    /// Replays a recorded sequence of instants.
+    ///
+    /// The returned iterator advances the time to the next instant of `schedule` every time it is
+    /// stepped, firing the alarms that are due on the way, and yields that instant. This allows
+    /// polling the code under test between steps, so that timing captured on a device drives it
+    /// deterministically:
+    ///
+    /// ```ignore
+    /// for at in MockDriver::get().replay(&recorded) {
+    ///     // poll the futures under test
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Stepping the iterator panics if the next instant is earlier than the current time,
+    /// for example if `schedule` isn't sorted.
+    pub fn replay<'a>(&'a self, schedule: &'a [Instant]) -> Replay<'a> {
+        Replay {
+            driver: self,
+            schedule: schedule.iter(),
+        }
+    }
+
     /// Advances the time by the specified [`Duration`].
     /// Calling any alarm callbacks that are due.
+    ///
+    /// Alarms are fired in chronological order: time is stepped to each due alarm's
+    /// deadline in turn before moving on to the final time. Alarms with the same deadline are
+    /// fired in the order they were scheduled.
     pub fn advance(&self, duration: Duration) {
         critical_section::with(|cs| {
             let inner = &mut *self.0.borrow_ref_mut(cs);
 
-            inner.now += duration;
-            // wake expired tasks.
-            inner.queue.next_expiration(inner.now.as_ticks());
+            let target = inner.now + duration;
+            // wake expired tasks, one deadline at a time.
+            loop {
+                let next = inner.queue.next_expiration(inner.now.as_ticks());
+                if next > target.as_ticks() || next == u64::MAX {
+                    break;
+                }
+                inner.now = Instant::from_ticks(next);
+            }
+            inner.now = target;
         })
     }
 }
@@ -81,10 +304,56 @@ impl Driver for MockDriver {
     }
 }
 
+/// Iterator returned by [`MockDriver::replay`].
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Replay<'a> {
+    driver: &'a MockDriver,
+    schedule: core::slice::Iter<'a, Instant>,
+}
+
+impl Iterator for Replay<'_> {
+    type Item = Instant;
+
+    fn next(&mut self) -> Option<Instant> {
+        let at = *self.schedule.next()?;
+        let now = self.driver.now_instant();
+        assert!(
+            at >= now,
+            "replayed instant {} is earlier than the current time {}",
+            at,
+            now
+        );
+        self.driver.advance(at - now);
+        Some(at)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.schedule.size_hint()
+    }
+}
+
+/// Formats a list of deadlines in ticks as comma-separated [`Instant`]s.
+struct DeadlineList<'a>(&'a [u64]);
+
+impl core::fmt::Display for DeadlineList<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, &at) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", Instant::from_ticks(at))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 struct InnerMockDriver {
     now: Instant,
     queue: Queue,
+    initialized: bool,
+    auto_advance: bool,
 }
 
 impl InnerMockDriver {
@@ -92,24 +361,41 @@ impl InnerMockDriver {
         Self {
             now: Instant::from_ticks(0),
             queue: Queue::new(),
+            initialized: true,
+            auto_advance: false,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use core::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::Arc;
+    use core::sync::atomic::AtomicBool;
+
+    use std::sync::{Arc, Mutex};
     use std::task::Wake;
+    use std::vec::Vec;
 
     use serial_test::serial;
 
     use super::*;
+    use crate::{Ticker, Timer};
 
     fn setup() {
         DRIVER.reset();
     }
 
+    /// A waker that appends `id` to `log` when woken, to check the order alarms fire in.
+    struct RecordingWaker {
+        id: u32,
+        log: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl Wake for RecordingWaker {
+        fn wake(self: Arc<Self>) {
+            self.log.lock().unwrap().push(self.id);
+        }
+    }
+
     #[test]
     #[serial]
     fn test_advance() {
@@ -121,6 +407,38 @@ mod tests {
         assert_eq!(Duration::from_secs(1).as_ticks(), driver.now() - reference);
     }
 
+    #[test]
+    #[serial]
+    fn test_reset() {
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+
+        struct MockWaker;
+
+        impl Wake for MockWaker {
+            fn wake(self: Arc<Self>) {
+                WOKEN.store(true, Ordering::Relaxed);
+            }
+        }
+
+        // A first test case leaves an alarm and modified settings behind.
+        setup();
+        let driver = MockDriver::get();
+        driver.advance(Duration::from_secs(5));
+        driver.schedule_wake(Instant::from_secs(10).as_ticks(), &Arc::new(MockWaker).into());
+        driver.set_auto_advance(true);
+        driver.set_initialized(false);
+
+        // The next one starts from scratch.
+        setup();
+        assert_eq!(driver.now_instant(), Instant::from_ticks(0));
+        assert_eq!(driver.next_alarm(), None);
+        assert_eq!(driver.try_now(), Some(0));
+        assert!(!driver.on_idle());
+
+        driver.advance(Duration::from_secs(20));
+        assert!(!WOKEN.load(Ordering::Relaxed));
+    }
+
     #[test]
     #[serial]
     fn test_schedule_wake() {
@@ -144,4 +462,311 @@ mod tests {
         driver.advance(Duration::from_secs(1));
         assert_eq!(true, CALLBACK_CALLED.load(Ordering::Relaxed));
     }
+
+    #[test]
+    #[serial]
+    fn test_now_instant() {
+        setup();
+
+        let driver = MockDriver::get();
+        assert_eq!(driver.now_instant(), Instant::from_ticks(0));
+        driver.advance(Duration::from_millis(1500));
+        assert_eq!(driver.now_instant(), Instant::from_millis(1500));
+        assert_eq!(driver.now_instant(), Instant::now());
+    }
+
+    #[test]
+    #[serial]
+    fn test_now_or_zero() {
+        setup();
+
+        let driver = MockDriver::get();
+        driver.advance(Duration::from_secs(1));
+
+        driver.set_initialized(false);
+        assert_eq!(Instant::try_now(), None);
+        assert_eq!(Instant::now_or_zero(), Instant::ZERO);
+
+        driver.set_initialized(true);
+        assert_eq!(Instant::try_now(), Some(Instant::from_secs(1)));
+        assert_eq!(Instant::now_or_zero(), Instant::from_secs(1));
+    }
+
+    #[test]
+    #[serial]
+    fn test_advance_fires_in_order() {
+        setup();
+
+        let driver = MockDriver::get();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        for (id, at) in [(3, 300), (1, 100), (2, 200), (4, 400)] {
+            let waker = Arc::new(RecordingWaker { id, log: log.clone() }).into();
+            driver.schedule_wake(Instant::from_millis(at).as_ticks(), &waker);
+        }
+
+        driver.advance(Duration::from_millis(350));
+        assert_eq!(*log.lock().unwrap(), [1, 2, 3]);
+        assert_eq!(driver.now_instant(), Instant::from_millis(350));
+    }
+
+    #[test]
+    #[serial]
+    fn test_advance_to_max() {
+        setup();
+
+        let driver = MockDriver::get();
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut timer = pin!(Timer::after_millis(100));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Pending);
+
+        driver.advance(Duration::MAX);
+        assert_eq!(driver.now_instant(), Instant::MAX);
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Ready(()));
+
+        // Nothing left in the queue, which must not be mistaken for an alarm due at the end of time.
+        setup();
+        driver.advance(Duration::MAX);
+        assert_eq!(driver.now_instant(), Instant::MAX);
+    }
+
+    #[test]
+    #[serial]
+    fn test_same_deadline_fires_in_schedule_order() {
+        setup();
+
+        let driver = MockDriver::get();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // The earlier alarm is dequeued first, which must not reorder the ones left behind it.
+        for (id, at) in [(0, 100), (1, 200), (2, 200), (3, 200)] {
+            let waker = Arc::new(RecordingWaker { id, log: log.clone() }).into();
+            driver.schedule_wake(Instant::from_millis(at).as_ticks(), &waker);
+        }
+
+        driver.advance(Duration::from_millis(200));
+        assert_eq!(*log.lock().unwrap(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_timer_completes_after_advance() {
+        setup();
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+
+        struct FlagWaker;
+
+        impl Wake for FlagWaker {
+            fn wake(self: Arc<Self>) {
+                WOKEN.store(true, Ordering::Relaxed);
+            }
+        }
+        WOKEN.store(false, Ordering::Relaxed);
+        let waker = Arc::new(FlagWaker).into();
+        let mut cx = Context::from_waker(&waker);
+
+        let driver = MockDriver::get();
+        let mut timer = pin!(Timer::after_secs(1));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Pending);
+
+        driver.advance(Duration::from_millis(999));
+        assert!(!WOKEN.load(Ordering::Relaxed));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Pending);
+
+        driver.advance(Duration::from_millis(1));
+        assert!(WOKEN.load(Ordering::Relaxed));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_advance_to_next_alarm() {
+        setup();
+
+        let driver = MockDriver::get();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(driver.next_alarm(), None);
+        assert_eq!(driver.advance_to_next_alarm(), None);
+
+        let mut late = pin!(Timer::after_millis(300));
+        let mut early = pin!(Timer::after_millis(100));
+        assert_eq!(late.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(early.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(driver.next_alarm(), Some(Instant::from_millis(100)));
+
+        // Reading the next alarm doesn't fire anything.
+        assert_eq!(driver.next_alarm(), Some(Instant::from_millis(100)));
+        assert_eq!(driver.now_instant(), Instant::from_ticks(0));
+        assert_eq!(early.as_mut().poll(&mut cx), Poll::Pending);
+
+        assert_eq!(driver.advance_to_next_alarm(), Some(Instant::from_millis(100)));
+        assert_eq!(driver.now_instant(), Instant::from_millis(100));
+        assert_eq!(early.as_mut().poll(&mut cx), Poll::Ready(()));
+        assert_eq!(late.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(driver.next_alarm(), Some(Instant::from_millis(300)));
+
+        assert_eq!(driver.advance_to_next_alarm(), Some(Instant::from_millis(300)));
+        assert_eq!(late.as_mut().poll(&mut cx), Poll::Ready(()));
+        assert_eq!(driver.next_alarm(), None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_replay() {
+        setup();
+
+        let driver = MockDriver::get();
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // Record when a ticker fires while time advances in irregular steps.
+        let mut recorded = Vec::new();
+        let mut ticker = Ticker::every(Duration::from_millis(100));
+        for step in [30, 80, 5, 150, 60, 240] {
+            driver.advance(Duration::from_millis(step));
+            while pin!(ticker.next()).poll(&mut cx).is_ready() {
+                recorded.push(driver.now_instant());
+            }
+        }
+        assert_eq!(recorded.len(), 5);
+
+        // Replaying the firing instants reproduces exactly the same pattern.
+        setup();
+        let mut ticker = Ticker::every(Duration::from_millis(100));
+        let mut replayed = Vec::new();
+        for at in driver.replay(&recorded) {
+            assert_eq!(driver.now_instant(), at);
+            while pin!(ticker.next()).poll(&mut cx).is_ready() {
+                replayed.push(driver.now_instant());
+            }
+        }
+        assert_eq!(replayed, recorded);
+    }
+
+    #[test]
+    #[serial]
+    fn test_replay_timer_order() {
+        setup();
+
+        let driver = MockDriver::get();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        for (id, ms) in [(1, 300), (2, 50), (3, 120), (4, 130)] {
+            let waker = Arc::new(RecordingWaker { id, log: log.clone() }).into();
+            driver.schedule_wake(Instant::from_millis(ms).as_ticks(), &waker);
+        }
+
+        let recorded = [40, 100, 100, 250, 400].map(Instant::from_millis);
+        let mut fired = Vec::new();
+        for at in driver.replay(&recorded) {
+            fired.push((at.as_millis(), log.lock().unwrap().clone()));
+        }
+
+        // Alarms fire as the replayed clock crosses them, soonest first.
+        assert_eq!(
+            fired,
+            [
+                (40, vec![]),
+                (100, vec![2]),
+                (100, vec![2]),
+                (250, vec![2, 3, 4]),
+                (400, vec![2, 3, 4, 1]),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    #[should_panic(expected = "earlier than the current time")]
+    fn test_replay_unsorted() {
+        setup();
+
+        let schedule = [Instant::from_millis(200), Instant::from_millis(100)];
+        MockDriver::get().replay(&schedule).for_each(drop);
+    }
+
+    #[test]
+    #[serial]
+    fn test_no_pending_alarms() {
+        setup();
+
+        let driver = MockDriver::get();
+        driver.assert_no_pending_alarms();
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut timer = pin!(Timer::after_millis(100));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Pending);
+        driver.advance(Duration::from_millis(100));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Ready(()));
+        driver.assert_no_pending_alarms();
+    }
+
+    #[test]
+    #[serial]
+    #[should_panic(expected = "2 alarm(s) still pending at 0 ticks, due at 100000 ticks, 300000 ticks")]
+    fn test_leaked_timer() {
+        setup();
+
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        // Distinct wakers, so the alarms aren't merged into one.
+        for (ms, waker) in [(300, Arc::new(NoopWaker)), (100, Arc::new(NoopWaker))] {
+            let waker = waker.into();
+            let mut timer = pin!(Timer::after_millis(ms));
+            assert_eq!(timer.as_mut().poll(&mut Context::from_waker(&waker)), Poll::Pending);
+        }
+        MockDriver::get().assert_no_pending_alarms();
+    }
+
+    #[test]
+    #[serial]
+    fn test_auto_advance() {
+        setup();
+
+        let driver = MockDriver::get();
+        driver.set_auto_advance(true);
+        driver.block_on(Timer::after_secs(3600));
+        assert_eq!(driver.now_instant(), Instant::from_secs(3600));
+
+        let mut ticker = Ticker::every(Duration::from_millis(250));
+        driver.block_on(async {
+            for _ in 0..4 {
+                ticker.next().await;
+            }
+        });
+        assert_eq!(driver.now_instant(), Instant::from_secs(3601));
+    }
+
+    #[test]
+    #[serial]
+    fn test_on_idle() {
+        setup();
+
+        let driver = MockDriver::get();
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut timer = pin!(Timer::after_secs(5));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Pending);
+
+        // Disabled by default.
+        assert!(!driver.on_idle());
+        assert_eq!(driver.now_instant(), Instant::from_ticks(0));
+
+        driver.set_auto_advance(true);
+        assert!(driver.on_idle());
+        assert_eq!(driver.now_instant(), Instant::from_secs(5));
+        assert_eq!(timer.as_mut().poll(&mut cx), Poll::Ready(()));
+        assert!(!driver.on_idle());
+    }
+
+    #[test]
+    #[serial]
+    #[should_panic(expected = "never complete")]
+    fn test_auto_advance_deadlock() {
+        setup();
+
+        let driver = MockDriver::get();
+        driver.set_auto_advance(true);
+        driver.block_on(core::future::pending::<()>());
+    }
 }
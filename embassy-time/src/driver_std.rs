@@ -1,3 +1,9 @@
+//! Time driver for `std` environments.
+//!
+//! By default, time is read from [`std::time::Instant`], which is monotonic: `Instant::now()` never
+//! goes backwards, even when the system clock is adjusted. With the `std-system-clock` feature, time
+//! is read from [`std::time::SystemTime`] instead, as microseconds since the UNIX epoch.
+
 use std::sync::{Condvar, Mutex};
 use std::thread;
 use std::time::{Duration as StdDuration, Instant as StdInstant};
@@ -38,7 +44,7 @@ impl Driver for TimeDriver {
     fn now(&self) -> u64 {
         let mut inner = self.inner.lock().unwrap();
         let zero = inner.init();
-        StdInstant::now().duration_since(zero).as_micros() as u64
+        clock_now(zero)
     }
 
     fn schedule_wake(&self, at: u64, waker: &core::task::Waker) {
@@ -50,6 +56,37 @@ impl Driver for TimeDriver {
     }
 }
 
+#[cfg(not(feature = "std-system-clock"))]
+fn clock_now(zero: StdInstant) -> u64 {
+    StdInstant::now().duration_since(zero).as_micros() as u64
+}
+
+#[cfg(feature = "std-system-clock")]
+fn clock_now(_zero: StdInstant) -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // A system clock set before the epoch reads as zero.
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as u64)
+}
+
+/// Returns the instant at which the alarm thread should wake up for an alarm at `at`.
+#[cfg(not(feature = "std-system-clock"))]
+fn alarm_deadline(zero: StdInstant, _now: u64, at: u64) -> Option<StdInstant> {
+    zero.checked_add(StdDuration::from_micros(at))
+}
+
+/// Returns the instant at which the alarm thread should wake up for an alarm at `at`.
+///
+/// The system clock can jump, so wake up at least once per second to notice alarms that
+/// became due because the clock was set forward.
+#[cfg(feature = "std-system-clock")]
+fn alarm_deadline(_zero: StdInstant, now: u64, at: u64) -> Option<StdInstant> {
+    let wait = StdDuration::from_micros(at.saturating_sub(now)).min(StdDuration::from_secs(1));
+    StdInstant::now().checked_add(wait)
+}
+
 fn alarm_thread() {
     let zero = DRIVER.inner.lock().unwrap().zero_instant.unwrap();
     loop {
@@ -58,9 +95,8 @@ fn alarm_thread() {
         let next_alarm = DRIVER.inner.lock().unwrap().queue.next_expiration(now);
 
         // Ensure we don't overflow
-        let until = zero
-            .checked_add(StdDuration::from_micros(next_alarm))
-            .unwrap_or_else(|| StdInstant::now() + StdDuration::from_secs(1));
+        let until =
+            alarm_deadline(zero, now, next_alarm).unwrap_or_else(|| StdInstant::now() + StdDuration::from_secs(1));
 
         DRIVER.signaler.wait_until(until);
     }
@@ -105,3 +141,24 @@ impl Signaler {
         self.condvar.notify_one();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration as StdDuration;
+
+    use crate::Instant;
+
+    #[test]
+    fn now_is_monotonic() {
+        let mut last = Instant::now();
+        for i in 0..1000 {
+            if i % 100 == 0 {
+                thread::sleep(StdDuration::from_millis(1));
+            }
+            let now = Instant::now();
+            assert!(now >= last, "time went backwards: {:?} -> {:?}", last, now);
+            last = now;
+        }
+    }
+}
@@ -1,9 +1,18 @@
+//! Time driver for WASM.
+//!
+//! Time is read from `performance.now()`, which has sub-millisecond resolution. It is available both
+//! in Window and Worker contexts, but browsers may coarsen its resolution (down to 100us or more) to
+//! mitigate timing attacks. In environments without a `performance` global, the driver falls back to
+//! `Date.now()`, which only has millisecond resolution and is not monotonic.
+//!
+//! Wakeups are scheduled with `setTimeout`, whose granularity is 1ms at best, and is often clamped to
+//! 4ms or more by browsers. Alarms never fire early, but may fire late.
+
 use std::sync::Mutex;
 
 use embassy_time_driver::Driver;
 use embassy_time_queue_utils::Queue;
 use wasm_bindgen::prelude::*;
-use wasm_timer::Instant as StdInstant;
 
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -21,6 +30,48 @@ impl AlarmState {
 extern "C" {
     fn setTimeout(closure: &Closure<dyn FnMut()>, millis: u32) -> f64;
     fn clearTimeout(token: f64);
+
+    #[derive(Debug)]
+    type Performance;
+    #[wasm_bindgen(method)]
+    fn now(this: &Performance) -> f64;
+}
+
+/// Source of the driver's time, in milliseconds.
+#[derive(Debug)]
+enum Clock {
+    Performance(Performance),
+    Date,
+}
+
+impl Clock {
+    fn get() -> Self {
+        match js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance")) {
+            Ok(performance) if performance.is_object() => Clock::Performance(performance.unchecked_into()),
+            _ => Clock::Date,
+        }
+    }
+
+    fn now_millis(&self) -> f64 {
+        match self {
+            Clock::Performance(performance) => performance.now(),
+            Clock::Date => js_sys::Date::now(),
+        }
+    }
+}
+
+/// Time origin of the driver.
+#[derive(Debug)]
+struct Zero {
+    clock: Clock,
+    millis: f64,
+}
+
+impl Zero {
+    fn now(&self) -> u64 {
+        // Float to int casts saturate, so a clock going backwards reads as zero.
+        ((self.clock.now_millis() - self.millis) * 1000.0) as u64
+    }
 }
 
 #[derive(Debug)]
@@ -33,7 +84,7 @@ struct TimeDriver {
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 struct Inner {
     alarm: AlarmState,
-    zero_instant: Option<StdInstant>,
+    zero: Option<Zero>,
     queue: Queue,
     closure: Option<Closure<dyn FnMut()>>,
 }
@@ -42,7 +93,7 @@ unsafe impl Send for Inner {}
 
 embassy_time_driver::time_driver_impl!(static DRIVER: TimeDriver = TimeDriver {
     inner: Mutex::new(Inner{
-        zero_instant: None,
+        zero: None,
         queue: Queue::new(),
         alarm: AlarmState::new(),
         closure: None,
@@ -50,12 +101,16 @@ embassy_time_driver::time_driver_impl!(static DRIVER: TimeDriver = TimeDriver {
 });
 
 impl Inner {
-    fn init(&mut self) -> StdInstant {
-        *self.zero_instant.get_or_insert_with(StdInstant::now)
+    fn init(&mut self) -> &Zero {
+        self.zero.get_or_insert_with(|| {
+            let clock = Clock::get();
+            let millis = clock.now_millis();
+            Zero { clock, millis }
+        })
     }
 
     fn now(&mut self) -> u64 {
-        StdInstant::now().duration_since(self.zero_instant.unwrap()).as_micros() as u64
+        self.zero.as_ref().unwrap().now()
     }
 
     fn set_alarm(&mut self, timestamp: u64) -> bool {
@@ -67,9 +122,11 @@ impl Inner {
         if timestamp <= now {
             false
         } else {
-            let timeout = (timestamp - now) as u32;
+            // Round up, so the alarm doesn't fire before it's due. Browsers fire timeouts longer
+            // than `i32::MAX` milliseconds immediately, so clamp them; the alarm is re-armed when it fires.
+            let timeout = (timestamp - now).div_ceil(1000).min(i32::MAX as u64) as u32;
             let closure = self.closure.get_or_insert_with(|| Closure::new(dispatch));
-            self.alarm.token = Some(setTimeout(closure, timeout / 1000));
+            self.alarm.token = Some(setTimeout(closure, timeout));
 
             true
         }
@@ -79,8 +136,7 @@ impl Inner {
 impl Driver for TimeDriver {
     fn now(&self) -> u64 {
         let mut inner = self.inner.lock().unwrap();
-        let zero = inner.init();
-        StdInstant::now().duration_since(zero).as_micros() as u64
+        inner.init().now()
     }
 
     fn schedule_wake(&self, at: u64, waker: &core::task::Waker) {
@@ -107,3 +163,28 @@ fn dispatch() {
         next = inner.queue.next_expiration(now);
     }
 }
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::Instant;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn now_advances() {
+        let start = Instant::now();
+        // `performance.now()` may be coarsened by the browser, so spin until it ticks.
+        let mut now = start;
+        for _ in 0..1_000_000 {
+            now = Instant::now();
+            if now != start {
+                break;
+            }
+        }
+        assert!(now > start);
+        // Resolution is better than `setTimeout`'s.
+        assert!(now.duration_since(start).as_micros() < 1000);
+    }
+}
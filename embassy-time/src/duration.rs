@@ -1,12 +1,18 @@
 use core::fmt;
-use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
 
 use super::{GCD_1K, GCD_1M, TICK_HZ};
+use crate::conv::{convert, convert_ceil, div_ceil};
 use crate::GCD_1G;
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[repr(transparent)]
 /// Represents the difference between two [Instant](struct.Instant.html)s
+///
+/// With the `serde` feature, a `Duration` is serialized as its tick count. This representation
+/// depends on the tick rate, so it can't be exchanged between builds with different tick rates.
 pub struct Duration {
     pub(crate) ticks: u64,
 }
@@ -16,12 +22,29 @@ impl Duration {
     pub const MIN: Duration = Duration { ticks: u64::MIN };
     /// The largest value that can be represented by the `Duration` type.
     pub const MAX: Duration = Duration { ticks: u64::MAX };
+    /// A duration of zero time.
+    pub const ZERO: Duration = Duration { ticks: 0 };
 
     /// Tick count of the `Duration`.
     pub const fn as_ticks(&self) -> u64 {
         self.ticks
     }
 
+    /// Convert the `Duration` to days, rounding down.
+    pub const fn as_days(&self) -> u64 {
+        self.as_secs() / 86400
+    }
+
+    /// Convert the `Duration` to hours, rounding down.
+    pub const fn as_hours(&self) -> u64 {
+        self.as_secs() / 3600
+    }
+
+    /// Convert the `Duration` to minutes, rounding down.
+    pub const fn as_minutes(&self) -> u64 {
+        self.as_secs() / 60
+    }
+
     /// Convert the `Duration` to seconds, rounding down.
     pub const fn as_secs(&self) -> u64 {
         self.ticks / TICK_HZ
@@ -29,17 +52,46 @@ impl Duration {
 
     /// Convert the `Duration` to milliseconds, rounding down.
     pub const fn as_millis(&self) -> u64 {
-        self.ticks * (1000 / GCD_1K) / (TICK_HZ / GCD_1K)
+        convert(self.ticks, TICK_HZ, 1000, GCD_1K)
     }
 
     /// Convert the `Duration` to microseconds, rounding down.
     pub const fn as_micros(&self) -> u64 {
-        self.ticks * (1_000_000 / GCD_1M) / (TICK_HZ / GCD_1M)
+        convert(self.ticks, TICK_HZ, 1_000_000, GCD_1M)
     }
 
     /// Convert the `Duration` to nanoseconds, rounding down.
+    ///
+    /// This overflows for durations longer than `u64::MAX` nanoseconds, about 584 years. Use
+    /// [`as_nanos_u128`](Self::as_nanos_u128) if the duration can be that long.
     pub const fn as_nanos(&self) -> u64 {
-        self.ticks * (1_000_000_000 / GCD_1G) / (TICK_HZ / GCD_1G)
+        convert(self.ticks, TICK_HZ, 1_000_000_000, GCD_1G)
+    }
+
+    /// Convert the `Duration` to nanoseconds, rounding down.
+    ///
+    /// Unlike [`as_nanos`](Self::as_nanos), this can't overflow, even for [`Duration::MAX`].
+    pub const fn as_nanos_u128(&self) -> u128 {
+        self.ticks as u128 * (1_000_000_000 / GCD_1G) as u128 / (TICK_HZ / GCD_1G) as u128
+    }
+
+    /// Split the `Duration` into whole seconds and the remaining nanoseconds, rounding down, like
+    /// [`core::time::Duration::as_secs`] and [`subsec_nanos`](core::time::Duration::subsec_nanos).
+    ///
+    /// The nanoseconds are always less than a second. Unlike [`as_nanos`](Self::as_nanos), this
+    /// can't overflow, even for [`Duration::MAX`].
+    pub const fn as_secs_and_nanos(&self) -> (u64, u32) {
+        let nanos = (self.ticks % TICK_HZ) as u128 * (1_000_000_000 / GCD_1G) as u128 / (TICK_HZ / GCD_1G) as u128;
+        (self.ticks / TICK_HZ, nanos as u32)
+    }
+
+    /// Returns a wrapper that displays the `Duration` with all its non-zero components,
+    /// such as `1h2m3s500ms`.
+    ///
+    /// The available units are `h`, `m`, `s`, `ms` and `us`. Anything below a microsecond is
+    /// truncated, and a duration shorter than that is displayed as `0s`.
+    pub const fn display_compact(&self) -> DisplayCompact {
+        DisplayCompact { duration: *self }
     }
 
     /// Creates a duration from the specified number of clock ticks
@@ -48,14 +100,39 @@ impl Duration {
     }
 
     /// Creates a duration from the specified number of seconds, rounding up.
+    ///
+    /// Saturates to [`Duration::MAX`] if the number of seconds is too large.
     pub const fn from_secs(secs: u64) -> Duration {
-        Duration { ticks: secs * TICK_HZ }
+        Duration {
+            ticks: secs.saturating_mul(TICK_HZ),
+        }
+    }
+
+    /// Creates a duration from the specified number of minutes.
+    ///
+    /// Saturates to [`Duration::MAX`] if the number of minutes is too large.
+    pub const fn from_minutes(minutes: u64) -> Duration {
+        Self::from_secs(minutes.saturating_mul(60))
+    }
+
+    /// Creates a duration from the specified number of hours.
+    ///
+    /// Saturates to [`Duration::MAX`] if the number of hours is too large.
+    pub const fn from_hours(hours: u64) -> Duration {
+        Self::from_secs(hours.saturating_mul(3600))
+    }
+
+    /// Creates a duration from the specified number of days.
+    ///
+    /// Saturates to [`Duration::MAX`] if the number of days is too large.
+    pub const fn from_days(days: u64) -> Duration {
+        Self::from_secs(days.saturating_mul(86400))
     }
 
     /// Creates a duration from the specified number of milliseconds, rounding up.
     pub const fn from_millis(millis: u64) -> Duration {
         Duration {
-            ticks: div_ceil(millis * (TICK_HZ / GCD_1K), 1000 / GCD_1K),
+            ticks: convert_ceil(millis, 1000, TICK_HZ, GCD_1K),
         }
     }
 
@@ -63,7 +140,7 @@ impl Duration {
     /// NOTE: Delays this small may be inaccurate.
     pub const fn from_micros(micros: u64) -> Duration {
         Duration {
-            ticks: div_ceil(micros * (TICK_HZ / GCD_1M), 1_000_000 / GCD_1M),
+            ticks: convert_ceil(micros, 1_000_000, TICK_HZ, GCD_1M),
         }
     }
 
@@ -71,19 +148,39 @@ impl Duration {
     /// NOTE: Delays this small may be inaccurate.
     pub const fn from_nanos(nanoseconds: u64) -> Duration {
         Duration {
-            ticks: div_ceil(nanoseconds * (TICK_HZ / GCD_1G), 1_000_000_000 / GCD_1G),
+            ticks: convert_ceil(nanoseconds, 1_000_000_000, TICK_HZ, GCD_1G),
+        }
+    }
+
+    /// Creates a duration from the specified number of seconds and nanoseconds, rounding up.
+    ///
+    /// `nanos` may be a second or more. This is the inverse of
+    /// [`as_secs_and_nanos`](Self::as_secs_and_nanos): converting back gives the same duration.
+    /// Saturates to [`Duration::MAX`] if the duration is too large.
+    pub const fn from_secs_and_nanos(secs: u64, nanos: u32) -> Duration {
+        Duration {
+            ticks: secs.saturating_mul(TICK_HZ).saturating_add(convert_ceil(
+                nanos as u64,
+                1_000_000_000,
+                TICK_HZ,
+                GCD_1G,
+            )),
         }
     }
 
     /// Creates a duration from the specified number of seconds, rounding down.
+    ///
+    /// Saturates to [`Duration::MAX`] if the number of seconds is too large.
     pub const fn from_secs_floor(secs: u64) -> Duration {
-        Duration { ticks: secs * TICK_HZ }
+        Duration {
+            ticks: secs.saturating_mul(TICK_HZ),
+        }
     }
 
     /// Creates a duration from the specified number of milliseconds, rounding down.
     pub const fn from_millis_floor(millis: u64) -> Duration {
         Duration {
-            ticks: millis * (TICK_HZ / GCD_1K) / (1000 / GCD_1K),
+            ticks: convert(millis, 1000, TICK_HZ, GCD_1K),
         }
     }
 
@@ -91,7 +188,15 @@ impl Duration {
     /// NOTE: Delays this small may be inaccurate.
     pub const fn from_micros_floor(micros: u64) -> Duration {
         Duration {
-            ticks: micros * (TICK_HZ / GCD_1M) / (1_000_000 / GCD_1M),
+            ticks: convert(micros, 1_000_000, TICK_HZ, GCD_1M),
+        }
+    }
+
+    /// Creates a duration from the specified number of nanoseconds, rounding down.
+    /// NOTE: Delays this small may be inaccurate.
+    pub const fn from_nanos_floor(nanoseconds: u64) -> Duration {
+        Duration {
+            ticks: convert(nanoseconds, 1_000_000_000, TICK_HZ, GCD_1G),
         }
     }
 
@@ -104,6 +209,33 @@ impl Duration {
         Some(Duration { ticks })
     }
 
+    /// Try to create a duration from the specified number of minutes.
+    /// Fails if the number of minutes is too large.
+    pub const fn try_from_minutes(minutes: u64) -> Option<Duration> {
+        let Some(secs) = minutes.checked_mul(60) else {
+            return None;
+        };
+        Self::try_from_secs(secs)
+    }
+
+    /// Try to create a duration from the specified number of hours.
+    /// Fails if the number of hours is too large.
+    pub const fn try_from_hours(hours: u64) -> Option<Duration> {
+        let Some(secs) = hours.checked_mul(3600) else {
+            return None;
+        };
+        Self::try_from_secs(secs)
+    }
+
+    /// Try to create a duration from the specified number of days.
+    /// Fails if the number of days is too large.
+    pub const fn try_from_days(days: u64) -> Option<Duration> {
+        let Some(secs) = days.checked_mul(86400) else {
+            return None;
+        };
+        Self::try_from_secs(secs)
+    }
+
     /// Try to create a duration from the specified number of milliseconds, rounding up.
     /// Fails if the number of milliseconds is too large.
     pub const fn try_from_millis(millis: u64) -> Option<Duration> {
@@ -171,6 +303,18 @@ impl Duration {
         })
     }
 
+    /// Try to create a duration from the specified number of nanoseconds, rounding down.
+    /// Fails if the number of nanoseconds is too large.
+    /// NOTE: Delays this small may be inaccurate.
+    pub const fn try_from_nanos_floor(nanoseconds: u64) -> Option<Duration> {
+        let Some(value) = nanoseconds.checked_mul(TICK_HZ / GCD_1G) else {
+            return None;
+        };
+        Some(Duration {
+            ticks: value / (1_000_000_000 / GCD_1G),
+        })
+    }
+
     /// Creates a duration corresponding to the specified Hz.
     /// NOTE: Giving this function a hz >= the TICK_HZ of your platform will clamp the Duration to 1
     /// tick. Doing so will not deadlock, but will certainly not produce the desired output.
@@ -186,23 +330,129 @@ impl Duration {
     }
 
     /// Adds one Duration to another, returning a new Duration or None in the event of an overflow.
-    pub fn checked_add(self, rhs: Duration) -> Option<Duration> {
-        self.ticks.checked_add(rhs.ticks).map(|ticks| Duration { ticks })
+    pub const fn checked_add(self, rhs: Duration) -> Option<Duration> {
+        match self.ticks.checked_add(rhs.ticks) {
+            Some(ticks) => Some(Duration { ticks }),
+            None => None,
+        }
     }
 
     /// Subtracts one Duration to another, returning a new Duration or None in the event of an overflow.
-    pub fn checked_sub(self, rhs: Duration) -> Option<Duration> {
-        self.ticks.checked_sub(rhs.ticks).map(|ticks| Duration { ticks })
+    pub const fn checked_sub(self, rhs: Duration) -> Option<Duration> {
+        match self.ticks.checked_sub(rhs.ticks) {
+            Some(ticks) => Some(Duration { ticks }),
+            None => None,
+        }
     }
 
     /// Multiplies one Duration by a scalar u32, returning a new Duration or None in the event of an overflow.
-    pub fn checked_mul(self, rhs: u32) -> Option<Duration> {
-        self.ticks.checked_mul(rhs as _).map(|ticks| Duration { ticks })
+    pub const fn checked_mul(self, rhs: u32) -> Option<Duration> {
+        match self.ticks.checked_mul(rhs as _) {
+            Some(ticks) => Some(Duration { ticks }),
+            None => None,
+        }
     }
 
     /// Divides one Duration a scalar u32, returning a new Duration or None in the event of an overflow.
-    pub fn checked_div(self, rhs: u32) -> Option<Duration> {
-        self.ticks.checked_div(rhs as _).map(|ticks| Duration { ticks })
+    pub const fn checked_div(self, rhs: u32) -> Option<Duration> {
+        match self.ticks.checked_div(rhs as _) {
+            Some(ticks) => Some(Duration { ticks }),
+            None => None,
+        }
+    }
+
+    /// Returns how many whole times `rhs` fits in this Duration, rounding down.
+    ///
+    /// For example, this gives how many periods of a [`Ticker`](crate::Ticker) fit in an interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub const fn div_duration(self, rhs: Duration) -> u64 {
+        self.ticks / rhs.ticks
+    }
+
+    /// Returns how many whole times `rhs` fits in this Duration, or None if `rhs` is zero.
+    pub const fn checked_div_duration(self, rhs: Duration) -> Option<u64> {
+        self.ticks.checked_div(rhs.ticks)
+    }
+
+    /// Returns the remainder of dividing this Duration by `rhs`, or None if `rhs` is zero.
+    ///
+    /// This is the offset into the current period of length `rhs`.
+    pub const fn checked_rem(self, rhs: Duration) -> Option<Duration> {
+        match self.ticks.checked_rem(rhs.ticks) {
+            Some(ticks) => Some(Duration { ticks }),
+            None => None,
+        }
+    }
+
+    /// Restricts the Duration to the range `[min, max]`.
+    ///
+    /// This is the same as [`Ord::clamp`], but usable in `const` contexts. `min` must not be greater
+    /// than `max`, which is checked in debug builds; in release builds the result is then `max`
+    /// for durations above it, and `min` otherwise.
+    pub const fn clamp_range(self, min: Duration, max: Duration) -> Duration {
+        // defmt can't be used in const fns, so this always uses the core macro.
+        ::core::debug_assert!(min.ticks <= max.ticks, "clamp_range: min is greater than max");
+        if self.ticks > max.ticks {
+            max
+        } else if self.ticks < min.ticks {
+            min
+        } else {
+            self
+        }
+    }
+
+    /// Multiplies the Duration by a floating point factor, rounding to the nearest tick.
+    ///
+    /// The result saturates to [`Duration::MAX`], and negative (or NaN) results are clamped to
+    /// [`Duration::ZERO`].
+    ///
+    /// The computation is done in `f64`, which represents integers exactly only up to 2^53. Tick counts
+    /// above that, which is over 285 years at 1MHz, lose precision even with a factor of `1.0`.
+    pub fn mul_f64(self, factor: f64) -> Duration {
+        Self::from_ticks_f64(self.ticks as f64 * factor)
+    }
+
+    /// Divides the Duration by a floating point divisor, rounding to the nearest tick.
+    ///
+    /// Saturation and precision are the same as for [`mul_f64`](Self::mul_f64). Dividing by zero
+    /// gives [`Duration::MAX`], unless the Duration is zero.
+    pub fn div_f64(self, divisor: f64) -> Duration {
+        Self::from_ticks_f64(self.ticks as f64 / divisor)
+    }
+
+    fn from_ticks_f64(ticks: f64) -> Duration {
+        if ticks.is_nan() || ticks <= 0.0 {
+            return Duration::ZERO;
+        }
+        // Float to int casts saturate, so values beyond `u64::MAX` become `Duration::MAX`.
+        Duration {
+            ticks: (ticks + 0.5) as u64,
+        }
+    }
+}
+
+/// Returns the shorter of two durations.
+///
+/// This is the same as [`Ord::min`], but usable in `const` contexts.
+pub const fn min_duration(a: Duration, b: Duration) -> Duration {
+    if a.ticks <= b.ticks {
+        a
+    } else {
+        b
+    }
+}
+
+/// Returns the longer of two durations.
+///
+/// This is the same as [`Ord::max`], but usable in `const` contexts.
+pub const fn max_duration(a: Duration, b: Duration) -> Duration {
+    if a.ticks >= b.ticks {
+        a
+    } else {
+        b
     }
 }
 
@@ -272,15 +522,188 @@ impl DivAssign<u32> for Duration {
     }
 }
 
+impl Rem for Duration {
+    type Output = Duration;
+
+    fn rem(self, rhs: Duration) -> Duration {
+        self.checked_rem(rhs)
+            .expect("divide by zero error when computing the remainder of a duration")
+    }
+}
+
+impl RemAssign for Duration {
+    fn rem_assign(&mut self, rhs: Duration) {
+        *self = *self % rhs;
+    }
+}
+
 impl<'a> fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} ticks", self.ticks)
     }
 }
 
-#[inline]
-const fn div_ceil(num: u64, den: u64) -> u64 {
-    (num + den - 1) / den
+/// Shows the tick count along with the duration in human units, such as
+/// `Duration(1500000 ticks ≈ 1s500ms)`, to make test failures easier to read.
+impl fmt::Debug for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Duration({} ticks ≈ {})", self.ticks, self.display_compact())
+    }
+}
+
+/// Error returned when parsing a [`Duration`] from a string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseDurationError {
+    /// The number is missing or malformed.
+    InvalidNumber,
+    /// The number isn't followed by a unit.
+    MissingUnit,
+    /// The unit isn't one of `ns`, `us`, `ms`, `s`, `m` or `h`.
+    InvalidUnit,
+    /// The duration is too large to be represented.
+    Overflow,
+}
+
+impl fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidNumber => "invalid number",
+            Self::MissingUnit => "missing unit",
+            Self::InvalidUnit => "invalid unit",
+            Self::Overflow => "duration too large",
+        })
+    }
+}
+
+/// Parses a number followed by a unit, such as `500ms`, `2s` or `1.5h`.
+///
+/// The number may have a fractional part. The units are `ns`, `us`, `ms`, `s`, `m` (minutes) and
+/// `h`. No whitespace or sign is allowed. Like [`Duration::from_millis`] and similar, the result is
+/// rounded up to a whole number of ticks. This doesn't allocate.
+impl core::str::FromStr for Duration {
+    type Err = ParseDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // More digits are truncated, they are below a nanosecond even for hours.
+        const MAX_FRACTION_DIGITS: usize = 15;
+
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or(ParseDurationError::MissingUnit)?;
+        let (number, unit) = s.split_at(split);
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(ParseDurationError::InvalidNumber);
+        }
+        let unit_nanos: u128 = match unit {
+            "ns" => 1,
+            "us" => 1_000,
+            "ms" => 1_000_000,
+            "s" => 1_000_000_000,
+            "m" => 60_000_000_000,
+            "h" => 3_600_000_000_000,
+            _ => return Err(ParseDurationError::InvalidUnit),
+        };
+
+        let fraction = &fraction[..fraction.len().min(MAX_FRACTION_DIGITS)];
+
+        let parse = |digits: &str| {
+            digits.bytes().try_fold(0u128, |acc, d| {
+                acc.checked_mul(10)
+                    .and_then(|acc| acc.checked_add((d - b'0') as u128))
+                    .ok_or(ParseDurationError::Overflow)
+            })
+        };
+        let scale = 10u128.pow(fraction.len() as u32);
+        let fraction = parse(fraction)? * unit_nanos;
+        // Round a sub-nanosecond remainder up, the tick conversion below rounds up as well.
+        let nanos = parse(whole)?
+            .checked_mul(unit_nanos)
+            .and_then(|nanos| nanos.checked_add(fraction.div_ceil(scale)))
+            .ok_or(ParseDurationError::Overflow)?;
+
+        let den = (1_000_000_000 / GCD_1G) as u128;
+        nanos
+            .checked_mul((TICK_HZ / GCD_1G) as u128)
+            .and_then(|n| n.checked_add(den - 1))
+            .and_then(|n| u64::try_from(n / den).ok())
+            .map(Duration::from_ticks)
+            .ok_or(ParseDurationError::Overflow)
+    }
+}
+
+/// Compact display of a [`Duration`], returned by [`Duration::display_compact`].
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayCompact {
+    duration: Duration,
+}
+
+impl fmt::Display for DisplayCompact {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Computed in u128 so that long durations with fast tick rates don't overflow.
+        let micros = self.duration.ticks as u128 * 1_000_000 / TICK_HZ as u128;
+        if micros == 0 {
+            return f.write_str("0s");
+        }
+
+        let components = [
+            (micros / 3_600_000_000, "h"),
+            (micros / 60_000_000 % 60, "m"),
+            (micros / 1_000_000 % 60, "s"),
+            (micros / 1000 % 1000, "ms"),
+            (micros % 1000, "us"),
+        ];
+        for (value, unit) in components {
+            if value != 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// SAFETY: `Duration` is a transparent wrapper around a `u64`, for which all bit patterns are valid.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Duration {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Duration {}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::Zero for Duration {
+    fn zero() -> Self {
+        Duration::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        self.ticks == 0
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::Bounded for Duration {
+    fn min_value() -> Self {
+        Duration::MIN
+    }
+
+    fn max_value() -> Self {
+        Duration::MAX
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::Saturating for Duration {
+    fn saturating_add(self, v: Self) -> Self {
+        Duration {
+            ticks: self.ticks.saturating_add(v.ticks),
+        }
+    }
+
+    fn saturating_sub(self, v: Self) -> Self {
+        Duration {
+            ticks: self.ticks.saturating_sub(v.ticks),
+        }
+    }
 }
 
 impl TryFrom<core::time::Duration> for Duration {
@@ -299,6 +722,82 @@ impl From<Duration> for core::time::Duration {
     }
 }
 
+// Conversions from and to `fugit` durations, with any tick type and tick period.
+#[cfg(feature = "fugit")]
+macro_rules! impl_fugit_conversions {
+    ($($int:ty),*) => {$(
+        /// Converts a `fugit` duration, rounding up to a whole number of ticks like
+        /// [`Duration::from_micros`]. Fails if the result doesn't fit.
+        ///
+        /// The conversion is exact if the `fugit` tick period is a whole number of ticks, for
+        /// example a [`fugit::MicrosDurationU64`] with a 1MHz tick rate. Otherwise, converting back
+        /// may not return the original value.
+        impl<const NOM: u32, const DENOM: u32> TryFrom<fugit::Duration<$int, NOM, DENOM>> for Duration {
+            type Error = <u64 as TryFrom<u128>>::Error;
+
+            fn try_from(value: fugit::Duration<$int, NOM, DENOM>) -> Result<Self, Self::Error> {
+                // If the numerator overflows, the result doesn't fit in a `u64` either.
+                let ticks = match (value.ticks() as u128).checked_mul(NOM as u128 * TICK_HZ as u128) {
+                    Some(num) => num.div_ceil(DENOM as u128),
+                    None => u128::MAX,
+                };
+                Ok(Duration::from_ticks(ticks.try_into()?))
+            }
+        }
+
+        /// Converts to a `fugit` duration, rounding down like [`Duration::as_micros`]. Fails if the
+        /// result doesn't fit.
+        ///
+        /// The conversion is exact if the tick period is a whole number of `fugit` ticks.
+        impl<const NOM: u32, const DENOM: u32> TryFrom<Duration> for fugit::Duration<$int, NOM, DENOM> {
+            type Error = <$int as TryFrom<u128>>::Error;
+
+            fn try_from(value: Duration) -> Result<Self, Self::Error> {
+                let ticks = value.ticks as u128 * DENOM as u128 / (NOM as u128 * TICK_HZ as u128);
+                Ok(Self::from_ticks(ticks.try_into()?))
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "fugit")]
+impl_fugit_conversions!(u32, u64);
+
+/// Compares to a [`core::time::Duration`] exactly.
+///
+/// Unlike the conversions, which go through microseconds and so round, this compares the exact
+/// values: a `core::time::Duration` is only equal to a `Duration` if it is a whole number of ticks.
+impl PartialEq<core::time::Duration> for Duration {
+    fn eq(&self, other: &core::time::Duration) -> bool {
+        self.partial_cmp(other) == Some(core::cmp::Ordering::Equal)
+    }
+}
+
+impl PartialEq<Duration> for core::time::Duration {
+    fn eq(&self, other: &Duration) -> bool {
+        other == self
+    }
+}
+
+/// Compares to a [`core::time::Duration`] exactly, see the [`PartialEq`] impl.
+impl PartialOrd<core::time::Duration> for Duration {
+    fn partial_cmp(&self, other: &core::time::Duration) -> Option<core::cmp::Ordering> {
+        // Compare `ticks / TICK_HZ` to `nanos / 1e9`. The left side can't overflow, and if the
+        // right side does, it is the larger one.
+        let ticks = self.ticks as u128 * 1_000_000_000;
+        Some(match other.as_nanos().checked_mul(TICK_HZ as u128) {
+            Some(nanos) => ticks.cmp(&nanos),
+            None => core::cmp::Ordering::Less,
+        })
+    }
+}
+
+impl PartialOrd<Duration> for core::time::Duration {
+    fn partial_cmp(&self, other: &Duration) -> Option<core::cmp::Ordering> {
+        other.partial_cmp(self).map(core::cmp::Ordering::reverse)
+    }
+}
+
 impl core::iter::Sum for Duration {
     fn sum<I>(iter: I) -> Self
     where
@@ -307,3 +806,422 @@ impl core::iter::Sum for Duration {
         Duration::from_ticks(iter.map(|d| d.as_ticks()).sum())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_nanos() {
+        // A tick in nanoseconds, rounded up, and the largest number of nanoseconds `from_nanos`
+        // can convert without overflowing.
+        let (tick_nanos, max) = match TICK_HZ {
+            32_768 => (30_518, u64::MAX / 64),
+            1_000_000 => (1_000, u64::MAX),
+            1_000_000_000 => (1, u64::MAX),
+            hz => (1_000_000_000u64.div_ceil(hz), u64::MAX / (hz / GCD_1G)),
+        };
+        for nanos in [0, 1, 999, 1_000, 123_456_789, max / 4] {
+            let d = Duration::from_nanos(nanos);
+            // `from_nanos` rounds up to whole ticks, and `as_nanos` rounds down.
+            assert!((nanos..nanos + tick_nanos).contains(&d.as_nanos()), "{nanos}");
+            assert_eq!(d.as_nanos_u128(), d.as_nanos() as u128);
+        }
+
+        let max_nanos = u64::MAX as u128 * 1_000_000_000 / TICK_HZ as u128;
+        assert_eq!(Duration::MAX.as_nanos_u128(), max_nanos);
+    }
+
+    #[test]
+    fn test_secs_and_nanos() {
+        let d = Duration::from_millis(1500);
+        assert_eq!(d.as_secs_and_nanos(), (1, 500_000_000));
+        assert_eq!(Duration::from_secs_and_nanos(1, 500_000_000), d);
+        assert_eq!(
+            Duration::from_secs_and_nanos(0, 2_500_000_000),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(Duration::from_secs_and_nanos(u64::MAX, u32::MAX), Duration::MAX);
+
+        // Nanoseconds that aren't a whole number of ticks are rounded up, and a tick converted back
+        // to nanoseconds is rounded down.
+        let tick_nanos = match TICK_HZ {
+            32_768 => 30_517,
+            1_000_000 => 1_000,
+            1_000_000_000 => 1,
+            hz => (1_000_000_000 / hz) as u32,
+        };
+        assert_eq!(
+            Duration::from_secs_and_nanos(2, 1),
+            Duration::from_ticks(2 * TICK_HZ + 1)
+        );
+        assert_eq!(
+            Duration::from_ticks(2 * TICK_HZ + 1).as_secs_and_nanos(),
+            (2, tick_nanos)
+        );
+
+        let (secs, nanos) = Duration::MAX.as_secs_and_nanos();
+        assert_eq!(secs, u64::MAX / TICK_HZ);
+        assert!(nanos < 1_000_000_000);
+        assert_eq!(Duration::from_secs_and_nanos(secs, nanos), Duration::MAX);
+    }
+
+    #[test]
+    fn test_clamp_range() {
+        let (min, max) = (Duration::from_millis(10), Duration::from_secs(1));
+        assert_eq!(Duration::from_millis(1).clamp_range(min, max), min);
+        assert_eq!(
+            Duration::from_millis(500).clamp_range(min, max),
+            Duration::from_millis(500)
+        );
+        assert_eq!(Duration::from_secs(30).clamp_range(min, max), max);
+        assert_eq!(min.clamp_range(min, max), min);
+        assert_eq!(max.clamp_range(min, max), max);
+        assert_eq!(Duration::MAX.clamp_range(min, min), min);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "min is greater than max")]
+    fn test_clamp_range_inverted() {
+        Duration::from_millis(500).clamp_range(Duration::from_secs(1), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_min_max_duration() {
+        let (a, b) = (Duration::from_millis(10), Duration::from_secs(1));
+        assert_eq!(min_duration(a, b), a);
+        assert_eq!(min_duration(b, a), a);
+        assert_eq!(max_duration(a, b), b);
+        assert_eq!(max_duration(b, a), b);
+        assert_eq!(max_duration(a, a), a);
+    }
+
+    #[test]
+    fn test_div_duration() {
+        let period = Duration::from_millis(250);
+        assert_eq!(Duration::from_secs(1).div_duration(period), 4);
+        assert_eq!(Duration::from_millis(999).div_duration(period), 3);
+        assert_eq!(Duration::from_millis(100).div_duration(period), 0);
+        assert_eq!(Duration::from_secs(1).checked_div_duration(period), Some(4));
+        assert_eq!(Duration::from_secs(1).checked_div_duration(Duration::ZERO), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_duration_by_zero() {
+        Duration::from_secs(1).div_duration(Duration::ZERO);
+    }
+
+    #[test]
+    fn test_rem() {
+        let period = Duration::from_millis(250);
+        assert_eq!(Duration::from_millis(1100) % period, Duration::from_millis(100));
+        assert_eq!(Duration::from_secs(1) % period, Duration::ZERO);
+        assert_eq!(Duration::from_millis(100) % period, Duration::from_millis(100));
+
+        let mut elapsed = Duration::from_millis(780);
+        elapsed %= period;
+        assert_eq!(elapsed, Duration::from_millis(30));
+
+        assert_eq!(Duration::from_secs(1).checked_rem(Duration::ZERO), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rem_by_zero() {
+        let _ = Duration::from_secs(1) % Duration::ZERO;
+    }
+
+    #[cfg(feature = "tick-hz-1_000_000")]
+    #[test]
+    fn test_cmp_core_duration() {
+        use core::time::Duration as CoreDuration;
+
+        let tick = Duration::from_ticks(1);
+        assert_eq!(tick, CoreDuration::from_micros(1));
+        assert_eq!(CoreDuration::from_micros(1), tick);
+
+        // 1.5us is between one and two ticks, and equal to neither, even though it converts to one tick.
+        let core = CoreDuration::from_nanos(1500);
+        assert_eq!(Duration::try_from(core), Ok(tick));
+        assert_ne!(tick, core);
+        assert!(tick < core);
+        assert!(core < Duration::from_ticks(2));
+        assert!(core > tick);
+
+        assert!(Duration::MAX < CoreDuration::MAX);
+        assert!(Duration::ZERO == CoreDuration::ZERO);
+    }
+
+    #[cfg(feature = "tick-hz-1_000_000")]
+    #[test]
+    fn test_floor_and_ceil() {
+        let tick = Duration::from_ticks(1);
+        let two_ticks = Duration::from_ticks(2);
+
+        // 1.5us rounds to one or two ticks.
+        assert_eq!(Duration::from_nanos(1500), two_ticks);
+        assert_eq!(Duration::from_nanos_floor(1500), tick);
+        assert_eq!(Duration::try_from_nanos(1500), Some(two_ticks));
+        assert_eq!(Duration::try_from_nanos_floor(1500), Some(tick));
+        assert_eq!(Duration::from_nanos_floor(999), Duration::ZERO);
+
+        // Whole ticks are the same either way.
+        for (ceil, floor) in [
+            (Duration::from_secs(2), Duration::from_secs_floor(2)),
+            (Duration::from_millis(3), Duration::from_millis_floor(3)),
+            (Duration::from_micros(4), Duration::from_micros_floor(4)),
+            (Duration::from_nanos(5000), Duration::from_nanos_floor(5000)),
+        ] {
+            assert_eq!(ceil, floor);
+        }
+        assert_eq!(Duration::try_from_secs_floor(2), Some(Duration::from_secs(2)));
+        assert_eq!(Duration::try_from_millis_floor(3), Some(Duration::from_millis(3)));
+        assert_eq!(Duration::try_from_micros_floor(4), Some(Duration::from_micros(4)));
+
+        assert_eq!(Duration::try_from_secs_floor(u64::MAX), None);
+        assert_eq!(Duration::try_from_millis_floor(u64::MAX), None);
+        // Below the tick period, nothing overflows.
+        assert_eq!(
+            Duration::try_from_nanos_floor(u64::MAX),
+            Some(Duration::from_ticks(u64::MAX / 1000))
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("2000ns".parse(), Ok(Duration::from_nanos(2000)));
+        assert_eq!("15us".parse(), Ok(Duration::from_micros(15)));
+        assert_eq!("1500ms".parse(), Ok(Duration::from_millis(1500)));
+        assert_eq!("2s".parse(), Ok(Duration::from_secs(2)));
+        assert_eq!("3m".parse(), Ok(Duration::from_minutes(3)));
+        assert_eq!("4h".parse(), Ok(Duration::from_hours(4)));
+
+        assert_eq!("1.5s".parse(), Ok(Duration::from_millis(1500)));
+        assert_eq!(".25s".parse(), Ok(Duration::from_millis(250)));
+        assert_eq!("2.s".parse(), Ok(Duration::from_secs(2)));
+        assert_eq!("0.5m".parse(), Ok(Duration::from_secs(30)));
+        // Rounded up to a whole tick, like `from_nanos`.
+        assert_eq!("1.0001ns".parse(), Ok(Duration::from_nanos(2)));
+
+        assert_eq!("500".parse::<Duration>(), Err(ParseDurationError::MissingUnit));
+        assert_eq!("".parse::<Duration>(), Err(ParseDurationError::MissingUnit));
+        assert_eq!("ms".parse::<Duration>(), Err(ParseDurationError::InvalidNumber));
+        assert_eq!(".s".parse::<Duration>(), Err(ParseDurationError::InvalidNumber));
+        assert_eq!("1.2.3s".parse::<Duration>(), Err(ParseDurationError::InvalidNumber));
+        assert_eq!("-1s".parse::<Duration>(), Err(ParseDurationError::InvalidNumber));
+        assert_eq!("5 ms".parse::<Duration>(), Err(ParseDurationError::InvalidUnit));
+        assert_eq!("5d".parse::<Duration>(), Err(ParseDurationError::InvalidUnit));
+
+        assert_eq!(
+            "99999999999999999999999999999999999999999s".parse::<Duration>(),
+            Err(ParseDurationError::Overflow)
+        );
+        // The largest number of seconds depends on the tick rate.
+        let max_secs = u64::MAX / TICK_HZ;
+        assert_eq!(format!("{max_secs}s").parse(), Ok(Duration::from_secs(max_secs)));
+        assert_eq!(
+            format!("{}s", max_secs + 1).parse::<Duration>(),
+            Err(ParseDurationError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_const_conversions() {
+        const DUR: Duration = Duration::from_millis(500);
+        const SUM: Option<Duration> = DUR.checked_add(Duration::from_secs(1));
+        const SECS: u64 = Duration::from_secs(3).as_secs();
+
+        assert_eq!(DUR.as_millis(), 500);
+        assert_eq!(SUM, Some(Duration::from_millis(1500)));
+        assert_eq!(SECS, 3);
+    }
+
+    #[test]
+    fn test_from_secs_saturates() {
+        assert_eq!(Duration::from_secs(u64::MAX), Duration::MAX);
+        assert_eq!(Duration::from_secs_floor(u64::MAX), Duration::MAX);
+        assert_eq!(Duration::try_from_secs(u64::MAX), None);
+        assert_eq!(Duration::from_secs(1).as_ticks(), TICK_HZ);
+    }
+
+    #[test]
+    fn test_days_hours_minutes() {
+        let day = Duration::from_days(1);
+        assert_eq!(day, Duration::from_secs(86400));
+        assert_eq!(day, Duration::from_hours(24));
+        assert_eq!(day, Duration::from_minutes(1440));
+        assert_eq!(Duration::try_from_days(1), Some(day));
+        assert_eq!((day.as_days(), day.as_hours(), day.as_minutes()), (1, 24, 1440));
+
+        // Rounds down.
+        let almost = day - Duration::from_ticks(1);
+        assert_eq!(
+            (almost.as_days(), almost.as_hours(), almost.as_minutes()),
+            (0, 23, 1439)
+        );
+    }
+
+    #[test]
+    fn test_days_overflow() {
+        let max_days = u64::MAX / TICK_HZ / 86400;
+        assert_eq!(
+            Duration::try_from_days(max_days),
+            Some(Duration::from_secs(max_days * 86400))
+        );
+        assert_eq!(Duration::try_from_days(max_days + 1), None);
+        assert_eq!(Duration::from_days(max_days + 1), Duration::MAX);
+        assert_eq!(Duration::try_from_days(u64::MAX), None);
+        assert_eq!(Duration::from_days(u64::MAX), Duration::MAX);
+
+        let max_hours = u64::MAX / TICK_HZ / 3600;
+        assert!(Duration::try_from_hours(max_hours).is_some());
+        assert_eq!(Duration::try_from_hours(max_hours + 1), None);
+        let max_minutes = u64::MAX / TICK_HZ / 60;
+        assert!(Duration::try_from_minutes(max_minutes).is_some());
+        assert_eq!(Duration::try_from_minutes(max_minutes + 1), None);
+    }
+
+    #[test]
+    fn test_mul_div_f64() {
+        let sec = Duration::from_secs(1);
+        assert_eq!(sec.mul_f64(1.5).as_ticks(), TICK_HZ * 3 / 2);
+        assert_eq!(sec.mul_f64(0.5).as_ticks(), TICK_HZ / 2);
+        assert_eq!(sec.div_f64(2.0).as_ticks(), TICK_HZ / 2);
+        assert_eq!(sec.div_f64(0.5).as_ticks(), TICK_HZ * 2);
+
+        // Rounds to nearest.
+        assert_eq!(Duration::from_ticks(3).mul_f64(0.5), Duration::from_ticks(2));
+        assert_eq!(Duration::from_ticks(3).mul_f64(0.4), Duration::from_ticks(1));
+
+        assert_eq!(sec.mul_f64(-1.0), Duration::ZERO);
+        assert_eq!(sec.mul_f64(f64::NAN), Duration::ZERO);
+        assert_eq!(sec.mul_f64(f64::INFINITY), Duration::MAX);
+        assert_eq!(Duration::MAX.mul_f64(2.0), Duration::MAX);
+        assert_eq!(sec.div_f64(0.0), Duration::MAX);
+        assert_eq!(Duration::ZERO.div_f64(0.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_display_compact() {
+        let d = Duration::from_secs(3723) + Duration::from_millis(500);
+        assert_eq!(format!("{}", d.display_compact()), "1h2m3s500ms");
+
+        // 7us is rounded up to a whole number of ticks, which is 30us at 32768Hz.
+        let d = Duration::from_secs(60) + Duration::from_micros(7);
+        let micros = Duration::from_micros(7).as_micros();
+        assert_eq!(format!("{}", d.display_compact()), format!("1m{micros}us"));
+
+        let d = Duration::from_secs(100 * 3600);
+        assert_eq!(format!("{}", d.display_compact()), "100h");
+    }
+
+    #[test]
+    fn test_debug() {
+        let d = Duration::from_millis(1500);
+        assert_eq!(
+            format!("{:?}", d),
+            format!("Duration({} ticks ≈ 1s500ms)", d.as_ticks())
+        );
+        assert_eq!(format!("{:?}", Duration::ZERO), "Duration(0 ticks ≈ 0s)");
+    }
+
+    #[test]
+    fn test_display_compact_zero() {
+        assert_eq!(format!("{}", Duration::from_ticks(0).display_compact()), "0s");
+        assert_eq!(format!("{}", Duration::MIN.display_compact()), "0s");
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_bytemuck_cast() {
+        let durations = [
+            Duration::from_ticks(1),
+            Duration::from_ticks(0x0102_0304_0506_0708),
+            Duration::MAX,
+        ];
+        let bytes: &[u8] = bytemuck::cast_slice(&durations);
+        assert_eq!(bytes.len(), 24);
+        assert_eq!(&bytes[8..16], &0x0102_0304_0506_0708u64.to_ne_bytes());
+
+        let back: &[Duration] = bytemuck::cast_slice(bytes);
+        assert_eq!(back, durations);
+        assert_eq!(bytemuck::cast::<u64, crate::Instant>(5), crate::Instant::from_ticks(5));
+        assert_eq!(<Duration as bytemuck::Zeroable>::zeroed(), Duration::ZERO);
+    }
+
+    #[cfg(feature = "fugit")]
+    #[test]
+    fn test_fugit() {
+        use fugit::{MicrosDurationU32, MicrosDurationU64, MillisDurationU32, SecsDurationU32, SecsDurationU64};
+
+        for micros in [0, 1, 999, 1_500_000] {
+            let duration = Duration::try_from(MicrosDurationU64::from_ticks(micros)).unwrap();
+            assert_eq!(duration, Duration::from_micros(micros));
+            assert_eq!(
+                MicrosDurationU64::try_from(duration).unwrap().ticks(),
+                duration.as_micros()
+            );
+        }
+
+        let duration = Duration::try_from(MillisDurationU32::from_ticks(1500)).unwrap();
+        assert_eq!(duration, Duration::from_millis(1500));
+        assert_eq!(
+            MillisDurationU32::try_from(duration),
+            Ok(MillisDurationU32::from_ticks(1500))
+        );
+        // Rounds down when the other tick period is longer.
+        assert_eq!(SecsDurationU32::try_from(duration), Ok(SecsDurationU32::from_ticks(1)));
+
+        assert!(Duration::try_from(SecsDurationU64::from_ticks(u64::MAX)).is_err());
+        assert!(MicrosDurationU32::try_from(Duration::from_secs(5000)).is_err());
+    }
+
+    #[cfg(feature = "num-traits")]
+    #[test]
+    fn test_num_traits() {
+        use num_traits::{Bounded, Saturating, Zero};
+
+        // Clamps a sum of values to `[0, max]`, written against num-traits only.
+        fn clamped_sum<T: Zero + Bounded + Saturating + Copy>(values: &[T]) -> (T, T) {
+            let sum = values.iter().fold(T::zero(), |acc, &v| acc.saturating_add(v));
+            (sum, T::max_value().saturating_sub(sum))
+        }
+
+        let (sum, left) = clamped_sum(&[Duration::from_secs(1), Duration::from_millis(500)]);
+        assert_eq!(sum, Duration::from_millis(1500));
+        assert_eq!(left, Duration::MAX - Duration::from_millis(1500));
+
+        let (sum, left) = clamped_sum(&[Duration::MAX, Duration::from_secs(1)]);
+        assert_eq!(sum, Duration::MAX);
+        assert!(left.is_zero());
+
+        let (sum, _) = clamped_sum::<Duration>(&[]);
+        assert!(sum.is_zero());
+        assert_eq!(Duration::min_value(), Duration::MIN);
+        assert_eq!(
+            Duration::from_secs(1).saturating_sub(Duration::from_secs(2)),
+            Duration::ZERO
+        );
+        assert_eq!(<crate::Instant as Bounded>::max_value(), crate::Instant::MAX);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use crate::Instant;
+
+        let duration = Duration::from_ticks(0x0102_0304_0506_0708);
+        let json = serde_json::to_string(&duration).unwrap();
+        assert_eq!(json, "72623859790382856");
+        assert_eq!(serde_json::from_str::<Duration>(&json).unwrap(), duration);
+
+        let instants = [Instant::MIN, Instant::from_ticks(42), Instant::MAX];
+        let json = serde_json::to_string(&instants).unwrap();
+        assert_eq!(json, format!("[0,42,{}]", u64::MAX));
+        assert_eq!(serde_json::from_str::<[Instant; 3]>(&json).unwrap(), instants);
+
+        assert!(serde_json::from_str::<Duration>("-1").is_err());
+    }
+}
@@ -3,9 +3,16 @@ use core::ops::{Add, AddAssign, Sub, SubAssign};
 
 use super::{Duration, GCD_1G, GCD_1K, GCD_1M, TICK_HZ};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[repr(transparent)]
 /// An Instant in time, based on the MCU's clock ticks since startup.
+///
+/// The `from_*` constructors, such as [`Instant::from_secs`], build an Instant from an uptime:
+/// they use the same epoch as [`Instant::now`], so they can be compared to it directly.
+///
+/// With the `serde` feature, an `Instant` is serialized as its tick count. This representation
+/// depends on the tick rate, so it can't be exchanged between builds with different tick rates.
 pub struct Instant {
     ticks: u64,
 }
@@ -15,6 +22,8 @@ impl Instant {
     pub const MIN: Instant = Instant { ticks: u64::MIN };
     /// The largest (latest) value that can be represented by the `Instant` type.
     pub const MAX: Instant = Instant { ticks: u64::MAX };
+    /// The instant of system boot, i.e. zero ticks.
+    pub const ZERO: Instant = Instant { ticks: 0 };
 
     /// Returns an Instant representing the current time.
     #[inline]
@@ -24,6 +33,34 @@ impl Instant {
         }
     }
 
+    /// Returns an Instant representing the current time, or `None` if the time driver
+    /// is not initialized yet.
+    ///
+    /// The time driver API has no way to report whether a driver is initialized, so only the
+    /// [mock driver](crate::MockDriver) can return `None` here. With any other driver this is
+    /// the same as [`Instant::now`], including what `now` does before the driver is set up.
+    #[inline]
+    pub fn try_now() -> Option<Instant> {
+        #[cfg(feature = "mock-driver")]
+        return crate::MockDriver::get().try_now().map(|ticks| Instant { ticks });
+        #[cfg(not(feature = "mock-driver"))]
+        return Some(Self::now());
+    }
+
+    /// Returns an Instant representing the current time, or [`Instant::ZERO`] if the time
+    /// driver is not initialized yet.
+    ///
+    /// This is a best-effort convenience for timestamps that are only informational,
+    /// such as log messages emitted very early during boot. Don't use it for measuring
+    /// time: all instants taken before the driver is initialized are equal.
+    ///
+    /// Detecting an uninitialized driver has the same limits as [`Instant::try_now`]: only
+    /// the mock driver is detected, other drivers are read as usual.
+    #[inline]
+    pub fn now_or_zero() -> Instant {
+        Self::try_now().unwrap_or(Self::ZERO)
+    }
+
     /// Create an Instant from a tick count since system boot.
     pub const fn from_ticks(ticks: u64) -> Self {
         Self { ticks }
@@ -104,6 +141,22 @@ impl Instant {
         self.ticks
     }
 
+    /// Splits the tick count into its high and low 32-bit words, as `(hi, lo)`.
+    ///
+    /// This is useful for drivers whose counter is made of two 32-bit registers.
+    pub const fn as_tick_words(&self) -> (u32, u32) {
+        ((self.ticks >> 32) as u32, self.ticks as u32)
+    }
+
+    /// Create an Instant from the high and low 32-bit words of a tick count.
+    ///
+    /// This is the inverse of [`Instant::as_tick_words`].
+    pub const fn from_tick_words(hi: u32, lo: u32) -> Self {
+        Self {
+            ticks: ((hi as u64) << 32) | lo as u64,
+        }
+    }
+
     /// Seconds since system boot.
     pub const fn as_secs(&self) -> u64 {
         self.ticks / TICK_HZ
@@ -124,6 +177,28 @@ impl Instant {
         self.ticks * (1_000_000_000 / GCD_1G) / (TICK_HZ / GCD_1G)
     }
 
+    /// Returns a value that displays the time since system boot as `HH:MM:SS.mmm`.
+    ///
+    /// The milliseconds are truncated. Uptimes of 100 hours or more are displayed with as many
+    /// hour digits as needed, e.g. `123:04:05.678`.
+    pub const fn display_uptime(&self) -> DisplayUptime {
+        DisplayUptime { instant: *self }
+    }
+
+    /// Mixes the tick count into a value suitable for seeding a pseudo-random number generator.
+    ///
+    /// The tick count is passed through the SplitMix64 finalizer, so that closely spaced
+    /// instants produce unrelated values. This is only as unpredictable as the moment the
+    /// instant was taken, e.g. a user pressing a button.
+    ///
+    /// **This is NOT cryptographically secure.** Only use it to seed non-cryptographic PRNGs.
+    pub const fn as_entropy_u64(&self) -> u64 {
+        let mut z = self.ticks.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
     /// Duration between this Instant and another Instant
     /// Panics on over/underflow.
     pub fn duration_since(&self, earlier: Instant) -> Duration {
@@ -133,7 +208,7 @@ impl Instant {
     }
 
     /// Duration between this Instant and another Instant
-    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+    pub const fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
         if self.ticks < earlier.ticks {
             None
         } else {
@@ -145,7 +220,7 @@ impl Instant {
 
     /// Returns the duration since the "earlier" Instant.
     /// If the "earlier" instant is in the future, the duration is set to zero.
-    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+    pub const fn saturating_duration_since(&self, earlier: Instant) -> Duration {
         Duration {
             ticks: if self.ticks < earlier.ticks {
                 0
@@ -155,34 +230,83 @@ impl Instant {
         }
     }
 
+    /// Returns the absolute difference between this Instant and another Instant.
+    ///
+    /// Unlike [`duration_since`](Self::duration_since), this doesn't depend on which of the
+    /// two is earlier.
+    pub const fn abs_diff(&self, other: Instant) -> Duration {
+        Duration {
+            ticks: self.ticks.abs_diff(other.ticks),
+        }
+    }
+
     /// Duration elapsed since this Instant.
+    ///
+    /// Like `std::time::Instant::elapsed`, this saturates to zero instead of panicking if this
+    /// Instant is in the future, for example if it was captured in an interrupt handler that
+    /// preempted the call to `Instant::now()`.
     pub fn elapsed(&self) -> Duration {
-        Instant::now() - *self
+        Instant::now().saturating_duration_since(*self)
     }
 
     /// Adds one Duration to self, returning a new `Instant` or None in the event of an overflow.
-    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
-        self.ticks.checked_add(duration.ticks).map(|ticks| Instant { ticks })
+    pub const fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        match self.ticks.checked_add(duration.ticks) {
+            Some(ticks) => Some(Instant { ticks }),
+            None => None,
+        }
     }
 
     /// Subtracts one Duration to self, returning a new `Instant` or None in the event of an overflow.
-    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
-        self.ticks.checked_sub(duration.ticks).map(|ticks| Instant { ticks })
+    pub const fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        match self.ticks.checked_sub(duration.ticks) {
+            Some(ticks) => Some(Instant { ticks }),
+            None => None,
+        }
     }
 
     /// Adds a Duration to self. In case of overflow, the maximum value is returned.
-    pub fn saturating_add(mut self, duration: Duration) -> Self {
+    ///
+    /// This makes far-future deadlines safe to compute: `Instant::now().saturating_add(Duration::MAX)`
+    /// is [`Instant::MAX`], which timers treat as never expiring, where the `+` operator would panic.
+    pub const fn saturating_add(mut self, duration: Duration) -> Self {
         self.ticks = self.ticks.saturating_add(duration.ticks);
         self
     }
 
     /// Subtracts a Duration from self. In case of overflow, the minimum value is returned.
-    pub fn saturating_sub(mut self, duration: Duration) -> Self {
+    pub const fn saturating_sub(mut self, duration: Duration) -> Self {
         self.ticks = self.ticks.saturating_sub(duration.ticks);
         self
     }
 }
 
+/// Returns an iterator over `count` instants, starting at `start` and spaced by `period`.
+///
+/// The `n`-th item is `start + n * period`. This is handy for precomputing a batch of alarms or
+/// expected timestamps in tests. The iterator ends early instead of overflowing if an instant
+/// would go past [`Instant::MAX`].
+pub fn schedule(start: Instant, period: Duration, count: usize) -> impl Iterator<Item = Instant> {
+    core::iter::successors(Some(start), move |t| t.checked_add(period)).take(count)
+}
+
+// SAFETY: `Instant` is a transparent wrapper around a `u64`, for which all bit patterns are valid.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Instant {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Instant {}
+
+#[cfg(feature = "num-traits")]
+impl num_traits::Bounded for Instant {
+    fn min_value() -> Self {
+        Instant::MIN
+    }
+
+    fn max_value() -> Self {
+        Instant::MAX
+    }
+}
+
 impl Add<Duration> for Instant {
     type Output = Instant;
 
@@ -226,3 +350,187 @@ impl<'a> fmt::Display for Instant {
         write!(f, "{} ticks", self.ticks)
     }
 }
+
+/// Shows the tick count along with the uptime, such as `Instant(3723456000 ticks ≈ 01:02:03.456)`,
+/// to make test failures easier to read.
+impl fmt::Debug for Instant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Instant({} ticks ≈ {})", self.ticks, self.display_uptime())
+    }
+}
+
+/// Uptime display of an [`Instant`], returned by [`Instant::display_uptime`].
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayUptime {
+    instant: Instant,
+}
+
+impl fmt::Display for DisplayUptime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Computed in u128 so that late instants with fast tick rates don't overflow.
+        let millis = self.instant.ticks as u128 * 1000 / TICK_HZ as u128;
+        write!(
+            f,
+            "{:02}:{:02}:{:02}.{:03}",
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            millis % 1000
+        )
+    }
+}
+
+/// Formats the instant as the uptime in seconds, with microsecond precision, like the
+/// `defmt-timestamp-uptime-us` timestamps.
+#[cfg(feature = "defmt")]
+impl defmt::Format for Instant {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u64:us}", self.as_micros())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_words() {
+        for ticks in [
+            0,
+            1,
+            u32::MAX as u64 - 1,
+            u32::MAX as u64,
+            1 << 32,
+            (1 << 32) + 1,
+            u64::MAX,
+        ] {
+            let instant = Instant::from_ticks(ticks);
+            let (hi, lo) = instant.as_tick_words();
+            assert_eq!(Instant::from_tick_words(hi, lo), instant);
+        }
+
+        assert_eq!(Instant::from_ticks(u32::MAX as u64).as_tick_words(), (0, u32::MAX));
+        assert_eq!(Instant::from_ticks(1 << 32).as_tick_words(), (1, 0));
+        assert_eq!(Instant::from_tick_words(u32::MAX, u32::MAX), Instant::MAX);
+    }
+
+    #[test]
+    fn test_from_uptime() {
+        assert_eq!(Instant::from_secs(1).as_ticks(), TICK_HZ);
+        assert_eq!(Instant::from_millis(1000), Instant::from_secs(1));
+        assert_eq!(Instant::from_micros(1_000_000), Instant::from_secs(1));
+
+        assert!(Instant::MIN < Instant::from_secs(1));
+        assert!(Instant::from_secs(1) < Instant::MAX);
+        assert_eq!(Instant::MIN, Instant::ZERO);
+    }
+
+    #[test]
+    fn test_display_uptime() {
+        let uptime = |instant: Instant| format!("{}", instant.display_uptime());
+        assert_eq!(uptime(Instant::ZERO), "00:00:00.000");
+        assert_eq!(uptime(Instant::from_millis(250)), "00:00:00.250");
+        // Milliseconds are rounded up to whole ticks, so that they display the same at 32768Hz.
+        assert_eq!(
+            uptime(Instant::from_secs(61) + Duration::from_millis(1)),
+            "00:01:01.001"
+        );
+        assert_eq!(
+            uptime(Instant::from_secs(3723) + Duration::from_millis(456)),
+            "01:02:03.456"
+        );
+        assert_eq!(uptime(Instant::from_secs(100 * 3600 + 59)), "100:00:59.000");
+        // Sub-millisecond parts are truncated.
+        assert_eq!(uptime(Instant::from_secs(1) - Duration::from_ticks(1)), "00:00:00.999");
+    }
+
+    #[test]
+    fn test_saturating_add() {
+        let near_max = Instant::MAX - Duration::from_ticks(10);
+        assert_eq!(
+            near_max.saturating_add(Duration::from_ticks(9)),
+            Instant::MAX - Duration::from_ticks(1)
+        );
+        assert_eq!(near_max.saturating_add(Duration::from_ticks(10)), Instant::MAX);
+        assert_eq!(near_max.saturating_add(Duration::from_ticks(11)), Instant::MAX);
+        assert_eq!(near_max.checked_add(Duration::from_ticks(11)), None);
+        assert_eq!(Instant::from_secs(1).saturating_add(Duration::MAX), Instant::MAX);
+        assert_eq!(
+            Instant::from_secs(1).saturating_add(Duration::from_secs(1)),
+            Instant::from_secs(2)
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let instant = Instant::from_secs(3723) + Duration::from_millis(456);
+        assert_eq!(
+            format!("{:?}", instant),
+            format!("Instant({} ticks ≈ 01:02:03.456)", instant.as_ticks())
+        );
+        assert_eq!(format!("{:?}", Instant::ZERO), "Instant(0 ticks ≈ 00:00:00.000)");
+    }
+
+    #[test]
+    fn test_abs_diff() {
+        let a = Instant::from_millis(100);
+        let b = Instant::from_millis(250);
+        assert_eq!(a.abs_diff(b), Duration::from_millis(150));
+        assert_eq!(b.abs_diff(a), Duration::from_millis(150));
+        assert_eq!(a.abs_diff(a), Duration::ZERO);
+        assert_eq!(Instant::MIN.abs_diff(Instant::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let start = Instant::from_millis(100);
+        let period = Duration::from_millis(25);
+        let instants: Vec<_> = schedule(start, period, 4).collect();
+        assert_eq!(instants, [0, 1, 2, 3].map(|n| start + period * n),);
+
+        assert_eq!(schedule(start, period, 0).count(), 0);
+
+        // Stops instead of overflowing.
+        let start = Instant::MAX - Duration::from_ticks(10);
+        let instants: Vec<_> = schedule(start, Duration::from_ticks(4), 10).collect();
+        assert_eq!(
+            instants,
+            [start, start + Duration::from_ticks(4), start + Duration::from_ticks(8)]
+        );
+    }
+
+    #[test]
+    fn test_as_entropy_u64() {
+        let a = Instant::from_ticks(1000).as_entropy_u64();
+        let b = Instant::from_ticks(1001).as_entropy_u64();
+
+        assert_ne!(a, b);
+        assert_ne!(a.wrapping_add(1), b);
+        // Adjacent ticks should flip roughly half of the output bits.
+        let flipped = (a ^ b).count_ones();
+        assert!((16..=48).contains(&flipped), "{flipped} bits flipped");
+
+        // The low bits should be spread evenly over a run of consecutive ticks.
+        let mut buckets = [0u32; 16];
+        for ticks in 0..1600 {
+            buckets[(Instant::from_ticks(ticks).as_entropy_u64() & 0xf) as usize] += 1;
+        }
+        assert!(buckets.iter().all(|&n| (50..=150).contains(&n)), "{buckets:?}");
+    }
+
+    #[cfg(feature = "mock-driver")]
+    #[test]
+    #[serial_test::serial]
+    fn test_elapsed() {
+        let driver = crate::MockDriver::get();
+        driver.reset();
+
+        let start = Instant::now();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        driver.advance(Duration::from_millis(1500));
+        assert_eq!(start.elapsed(), Duration::from_millis(1500));
+
+        // An instant in the future doesn't panic.
+        assert_eq!((Instant::now() + Duration::from_secs(1)).elapsed(), Duration::ZERO);
+    }
+}
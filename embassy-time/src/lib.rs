@@ -11,39 +11,50 @@
 // This mod MUST go first, so that the others see its macros.
 pub(crate) mod fmt;
 
+pub mod conv;
+#[cfg(feature = "defmt-timestamp-uptime-runtime")]
+mod defmt_timestamp;
 mod delay;
+mod delta_timer;
 mod duration;
 mod instant;
+mod signed_duration;
+mod soft_pwm;
+mod stopwatch;
 mod timer;
+mod wait_stats;
 
 #[cfg(feature = "mock-driver")]
 mod driver_mock;
 
 #[cfg(feature = "mock-driver")]
-pub use driver_mock::MockDriver;
+pub use driver_mock::{MockDriver, Replay};
 
 #[cfg(feature = "std")]
 mod driver_std;
 #[cfg(feature = "wasm")]
 mod driver_wasm;
 
-pub use delay::{block_for, Delay};
-pub use duration::Duration;
+#[cfg(feature = "defmt-timestamp-uptime-runtime")]
+pub use defmt_timestamp::{defmt_timestamp_unit, set_defmt_timestamp_unit, DefmtTimestampUnit};
+pub use delay::{block_for, block_while, Delay, DelayGuard};
+pub use delta_timer::DeltaTimer;
+pub use duration::{max_duration, min_duration, DisplayCompact, Duration, ParseDurationError};
 pub use embassy_time_driver::TICK_HZ;
-pub use instant::Instant;
-pub use timer::{with_deadline, with_timeout, Ticker, TimeoutError, Timer, WithTimeout};
+pub use instant::{schedule, DisplayUptime, Instant};
+pub use signed_duration::SignedDuration;
+pub use soft_pwm::SoftPwm;
+pub use stopwatch::Stopwatch;
+pub use timer::{
+    race_timers, with_deadline, with_optional_deadline, with_timeout, with_timeout_floor, with_timeout_remaining,
+    with_timeout_timed, AdaptiveTicker, Deadline, Either, LateTick, ReturningTimer, Ticker, TimeoutError, Timer,
+    WithTimeout,
+};
+pub use wait_stats::WaitStats;
 
-const fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-pub(crate) const GCD_1K: u64 = gcd(TICK_HZ, 1_000);
-pub(crate) const GCD_1M: u64 = gcd(TICK_HZ, 1_000_000);
-pub(crate) const GCD_1G: u64 = gcd(TICK_HZ, 1_000_000_000);
+pub(crate) const GCD_1K: u64 = conv::gcd(TICK_HZ, 1_000);
+pub(crate) const GCD_1M: u64 = conv::gcd(TICK_HZ, 1_000_000);
+pub(crate) const GCD_1G: u64 = conv::gcd(TICK_HZ, 1_000_000_000);
 
 #[cfg(feature = "defmt-timestamp-uptime-s")]
 defmt::timestamp! {"{=u64}", Instant::now().as_secs() }
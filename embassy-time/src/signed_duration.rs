@@ -0,0 +1,227 @@
+use core::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use crate::{Duration, Instant};
+
+/// A signed difference between two [`Instant`]s, such as a clock offset.
+///
+/// Unlike [`Duration`], this can be negative, for example when a measured interval is shorter
+/// than the nominal one. Get one with [`Instant::signed_duration_since`], or from a [`Duration`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(transparent)]
+pub struct SignedDuration {
+    ticks: i64,
+}
+
+impl SignedDuration {
+    /// The smallest (most negative) value that can be represented by the `SignedDuration` type.
+    pub const MIN: SignedDuration = SignedDuration { ticks: i64::MIN };
+    /// The largest value that can be represented by the `SignedDuration` type.
+    pub const MAX: SignedDuration = SignedDuration { ticks: i64::MAX };
+    /// A duration of zero time.
+    pub const ZERO: SignedDuration = SignedDuration { ticks: 0 };
+
+    /// Creates a signed duration from the specified number of clock ticks.
+    pub const fn from_ticks(ticks: i64) -> SignedDuration {
+        SignedDuration { ticks }
+    }
+
+    /// Tick count of the `SignedDuration`.
+    pub const fn as_ticks(&self) -> i64 {
+        self.ticks
+    }
+
+    /// Creates a signed duration from the specified number of seconds.
+    ///
+    /// Saturates to [`SignedDuration::MIN`] or [`SignedDuration::MAX`] if the number of seconds is too large.
+    pub const fn from_secs(secs: i64) -> SignedDuration {
+        Self::from_magnitude(secs < 0, Duration::from_secs(secs.unsigned_abs()))
+    }
+
+    /// Creates a signed duration from the specified number of milliseconds, rounding away from zero.
+    pub const fn from_millis(millis: i64) -> SignedDuration {
+        Self::from_magnitude(millis < 0, Duration::from_millis(millis.unsigned_abs()))
+    }
+
+    /// Convert the `SignedDuration` to seconds, rounding towards zero.
+    pub const fn as_secs(&self) -> i64 {
+        self.with_magnitude(self.unsigned_abs().as_secs())
+    }
+
+    /// Convert the `SignedDuration` to milliseconds, rounding towards zero.
+    pub const fn as_millis(&self) -> i64 {
+        self.with_magnitude(self.unsigned_abs().as_millis())
+    }
+
+    /// Returns the absolute value of the `SignedDuration`, as a [`Duration`].
+    pub const fn unsigned_abs(&self) -> Duration {
+        Duration::from_ticks(self.ticks.unsigned_abs())
+    }
+
+    /// Returns true if the `SignedDuration` is negative.
+    pub const fn is_negative(&self) -> bool {
+        self.ticks < 0
+    }
+
+    /// Adds one SignedDuration to another, returning a new SignedDuration or None in the event of an overflow.
+    pub const fn checked_add(self, rhs: SignedDuration) -> Option<SignedDuration> {
+        match self.ticks.checked_add(rhs.ticks) {
+            Some(ticks) => Some(SignedDuration { ticks }),
+            None => None,
+        }
+    }
+
+    /// Subtracts one SignedDuration from another, returning a new SignedDuration or None in the event of an overflow.
+    pub const fn checked_sub(self, rhs: SignedDuration) -> Option<SignedDuration> {
+        match self.ticks.checked_sub(rhs.ticks) {
+            Some(ticks) => Some(SignedDuration { ticks }),
+            None => None,
+        }
+    }
+
+    /// Negates the SignedDuration, returning None in the event of an overflow.
+    pub const fn checked_neg(self) -> Option<SignedDuration> {
+        match self.ticks.checked_neg() {
+            Some(ticks) => Some(SignedDuration { ticks }),
+            None => None,
+        }
+    }
+
+    /// Applies the sign, saturating if the magnitude doesn't fit.
+    const fn from_magnitude(negative: bool, magnitude: Duration) -> SignedDuration {
+        let ticks = magnitude.as_ticks();
+        let ticks = match negative {
+            true if ticks > i64::MIN.unsigned_abs() => i64::MIN,
+            true => 0i64.wrapping_sub_unsigned(ticks),
+            false if ticks > i64::MAX as u64 => i64::MAX,
+            false => ticks as i64,
+        };
+        SignedDuration { ticks }
+    }
+
+    /// Applies the sign of this SignedDuration to `magnitude`, which is never larger than its ticks.
+    const fn with_magnitude(&self, magnitude: u64) -> i64 {
+        match self.is_negative() {
+            true => 0i64.wrapping_sub_unsigned(magnitude),
+            false => magnitude as i64,
+        }
+    }
+}
+
+impl Add for SignedDuration {
+    type Output = SignedDuration;
+
+    fn add(self, rhs: SignedDuration) -> SignedDuration {
+        self.checked_add(rhs).expect("overflow when adding durations")
+    }
+}
+
+impl AddAssign for SignedDuration {
+    fn add_assign(&mut self, rhs: SignedDuration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for SignedDuration {
+    type Output = SignedDuration;
+
+    fn sub(self, rhs: SignedDuration) -> SignedDuration {
+        self.checked_sub(rhs).expect("overflow when subtracting durations")
+    }
+}
+
+impl SubAssign for SignedDuration {
+    fn sub_assign(&mut self, rhs: SignedDuration) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for SignedDuration {
+    type Output = SignedDuration;
+
+    fn neg(self) -> SignedDuration {
+        self.checked_neg().expect("overflow when negating duration")
+    }
+}
+
+impl From<Duration> for SignedDuration {
+    /// Saturates to [`SignedDuration::MAX`] if the duration is too large.
+    fn from(value: Duration) -> Self {
+        Self::from_magnitude(false, value)
+    }
+}
+
+impl TryFrom<SignedDuration> for Duration {
+    type Error = <u64 as TryFrom<i64>>::Error;
+
+    /// Fails if the duration is negative.
+    fn try_from(value: SignedDuration) -> Result<Self, Self::Error> {
+        Ok(Duration::from_ticks(value.ticks.try_into()?))
+    }
+}
+
+impl Instant {
+    /// Signed duration between this Instant and another Instant.
+    ///
+    /// The result is negative if `earlier` is actually later than this Instant. Saturates to
+    /// [`SignedDuration::MIN`] or [`SignedDuration::MAX`] if the difference is too large.
+    pub const fn signed_duration_since(&self, earlier: Instant) -> SignedDuration {
+        let (a, b) = (self.as_ticks(), earlier.as_ticks());
+        match a >= b {
+            true => SignedDuration::from_magnitude(false, Duration::from_ticks(a - b)),
+            false => SignedDuration::from_magnitude(true, Duration::from_ticks(b - a)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_duration_since() {
+        let a = Instant::from_millis(100);
+        let b = Instant::from_millis(250);
+        assert_eq!(b.signed_duration_since(a), SignedDuration::from_millis(150));
+        assert_eq!(a.signed_duration_since(b), SignedDuration::from_millis(-150));
+        assert_eq!(a.signed_duration_since(a), SignedDuration::ZERO);
+        assert_eq!(Instant::MAX.signed_duration_since(Instant::MIN), SignedDuration::MAX);
+        assert_eq!(Instant::MIN.signed_duration_since(Instant::MAX), SignedDuration::MIN);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // Multiples of 125ms are whole numbers of ticks at 32768Hz, as well as at decimal tick rates.
+        let nominal = SignedDuration::from(Duration::from_secs(1));
+        let offset = SignedDuration::from_millis(875) - nominal;
+        assert_eq!(offset, SignedDuration::from_millis(-125));
+        assert!(offset.is_negative());
+        assert_eq!(-offset, SignedDuration::from_millis(125));
+        assert_eq!(offset.unsigned_abs(), Duration::from_millis(125));
+
+        let mut total = offset;
+        total += SignedDuration::from_millis(250);
+        assert_eq!(total, SignedDuration::from_millis(125));
+        total -= SignedDuration::from_millis(375);
+        assert_eq!(total, SignedDuration::from_millis(-250));
+
+        assert_eq!(SignedDuration::MIN.checked_neg(), None);
+        assert_eq!(SignedDuration::MAX.checked_add(SignedDuration::from_ticks(1)), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(SignedDuration::from_secs(-2).as_millis(), -2000);
+        assert_eq!(SignedDuration::from_millis(-2500).as_secs(), -2);
+        assert_eq!(SignedDuration::from_millis(2500).as_secs(), 2);
+        assert_eq!(SignedDuration::from_secs(i64::MIN), SignedDuration::MIN);
+        assert_eq!(SignedDuration::from_secs(i64::MAX), SignedDuration::MAX);
+        assert_eq!(SignedDuration::from(Duration::MAX), SignedDuration::MAX);
+
+        assert_eq!(
+            Duration::try_from(SignedDuration::from_millis(5)),
+            Ok(Duration::from_millis(5))
+        );
+        assert!(Duration::try_from(SignedDuration::from_millis(-5)).is_err());
+    }
+}
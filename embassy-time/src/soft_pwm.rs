@@ -0,0 +1,205 @@
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::future::poll_fn;
+use core::task::{Poll, Waker};
+
+use crate::{Duration, Instant, Timer};
+
+/// Software PWM output, for low frequency signals such as LED dimming or driving a buzzer.
+///
+/// The output is driven by calling a caller-provided `set` closure with the new output level.
+/// The on and off times are scheduled from the start of the cycle, like [`Ticker`](crate::Ticker),
+/// so the period doesn't drift if the task is polled late.
+///
+/// Both [`run()`](Self::run) and [`set_duty()`](Self::set_duty) take `&self`, so the duty can be
+/// changed while the output is running, for example from another future joined with `run()`.
+///
+/// ``` no_run
+/// use embassy_time::{Duration, SoftPwm};
+/// # fn set_led(_on: bool) {}
+///
+/// #[embassy_executor::task]
+/// async fn dim_led() {
+///     // 20% duty cycle at 100Hz.
+///     let pwm = SoftPwm::new(Duration::from_millis(2), Duration::from_millis(8), set_led);
+///     pwm.run().await
+/// }
+/// ```
+pub struct SoftPwm<S: FnMut(bool)> {
+    duty: Cell<(Duration, Duration)>,
+    /// Woken when the duty changes while the output is held at a constant level.
+    waker: Cell<Option<Waker>>,
+    set: RefCell<S>,
+}
+
+impl<S: FnMut(bool)> fmt::Debug for SoftPwm<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (on, off) = self.duty.get();
+        f.debug_struct("SoftPwm")
+            .field("on", &on)
+            .field("off", &off)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: FnMut(bool)> SoftPwm<S> {
+    /// Create a new `SoftPwm` that keeps the output high for `on` and low for `off` in every cycle.
+    pub fn new(on: Duration, off: Duration, set: S) -> Self {
+        Self {
+            duty: Cell::new((on, off)),
+            waker: Cell::new(None),
+            set: RefCell::new(set),
+        }
+    }
+
+    /// Change the on and off times. This takes effect at the start of the next cycle, or right
+    /// away if the output is held at a constant level by a duty of 0% or 100%.
+    pub fn set_duty(&self, on: Duration, off: Duration) {
+        self.duty.set((on, off));
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Drive the output forever.
+    ///
+    /// With a duty of 0% (`on` is zero) or 100% (`off` is zero), the output is set once and not
+    /// toggled until the duty is changed. If both are zero, the output is kept low.
+    pub async fn run(&self) -> ! {
+        let mut next = Instant::now();
+        loop {
+            let (on, off) = self.duty.get();
+            if on == Duration::ZERO || off == Duration::ZERO {
+                self.output(on != Duration::ZERO);
+                poll_fn(|cx| {
+                    if self.duty.get() != (on, off) {
+                        return Poll::Ready(());
+                    }
+                    self.waker.set(Some(cx.waker().clone()));
+                    Poll::Pending
+                })
+                .await;
+                next = Instant::now();
+                continue;
+            }
+
+            self.output(true);
+            next += on;
+            Timer::at(next).await;
+
+            self.output(false);
+            next += off;
+            Timer::at(next).await;
+        }
+    }
+
+    fn output(&self, level: bool) {
+        (self.set.borrow_mut())(level)
+    }
+}
+
+#[cfg(all(test, feature = "mock-driver"))]
+mod tests {
+    use core::cell::RefCell;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Waker};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::MockDriver;
+
+    #[test]
+    #[serial]
+    fn test_soft_pwm() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let log = RefCell::new(Vec::new());
+        let pwm = SoftPwm::new(Duration::from_millis(2), Duration::from_millis(8), |on| {
+            log.borrow_mut().push((Instant::now().as_millis(), on))
+        });
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut fut = pin!(pwm.run());
+
+        for _ in 0..25 {
+            assert!(fut.as_mut().poll(&mut cx).is_pending());
+            driver.advance(Duration::from_millis(1));
+        }
+
+        assert_eq!(
+            *log.borrow(),
+            [(0, true), (2, false), (10, true), (12, false), (20, true), (22, false)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_soft_pwm_degenerate_duty() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        for (on, off, level) in [(0, 10, false), (10, 0, true), (0, 0, false)] {
+            let log = RefCell::new(Vec::new());
+            let pwm = SoftPwm::new(Duration::from_millis(on), Duration::from_millis(off), |on| {
+                log.borrow_mut().push(on)
+            });
+            let mut cx = Context::from_waker(Waker::noop());
+            let mut fut = pin!(pwm.run());
+
+            for _ in 0..5 {
+                assert!(fut.as_mut().poll(&mut cx).is_pending());
+                driver.advance(Duration::from_millis(10));
+            }
+            assert_eq!(*log.borrow(), [level]);
+            assert_eq!(driver.next_alarm(), None);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_soft_pwm_set_duty_while_running() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let log = RefCell::new(Vec::new());
+        let pwm = SoftPwm::new(Duration::from_millis(2), Duration::from_millis(8), |on| {
+            log.borrow_mut().push((Instant::now().as_millis(), on))
+        });
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut fut = pin!(pwm.run());
+
+        let mut step = |millis| {
+            for _ in 0..millis {
+                assert!(fut.as_mut().poll(&mut cx).is_pending());
+                driver.advance(Duration::from_millis(1));
+            }
+        };
+
+        // A new duty takes effect at the start of the next cycle.
+        step(5);
+        pwm.set_duty(Duration::from_millis(5), Duration::from_millis(5));
+        step(10);
+
+        // Leaving a constant level restarts the cycle right away.
+        pwm.set_duty(Duration::ZERO, Duration::from_millis(10));
+        step(7);
+        pwm.set_duty(Duration::from_millis(1), Duration::from_millis(1));
+        step(3);
+
+        assert_eq!(
+            *log.borrow(),
+            [
+                (0, true),
+                (2, false),
+                (10, true),
+                (15, false),
+                (20, false),
+                (22, true),
+                (23, false),
+                (24, true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Duration, Instant};
+
+/// Measures elapsed time across pauses, for benchmarking sections of code.
+///
+/// Time only accumulates while the stopwatch is running, between [`start()`](Self::start) and
+/// [`stop()`](Self::stop). Laps split the running time into consecutive intervals.
+///
+/// ``` no_run
+/// use embassy_time::Stopwatch;
+/// # fn step_one() {}
+/// # fn step_two() {}
+///
+/// let mut sw = Stopwatch::new();
+/// sw.start();
+/// step_one();
+/// let one = sw.lap();
+/// step_two();
+/// let two = sw.lap();
+/// sw.stop();
+/// assert_eq!(sw.elapsed(), one + two);
+/// ```
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stopwatch {
+    /// Time accumulated by the previous runs.
+    accumulated: Duration,
+    /// When the current run started, if running.
+    started: Option<Instant>,
+    /// Elapsed time at the end of the previous lap.
+    lap: Duration,
+}
+
+impl Stopwatch {
+    /// Create a new, stopped `Stopwatch` with no elapsed time.
+    pub const fn new() -> Self {
+        Self {
+            accumulated: Duration::from_ticks(0),
+            started: None,
+            lap: Duration::from_ticks(0),
+        }
+    }
+
+    /// Start or resume measuring time. Does nothing if the stopwatch is already running.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Pause measuring time, keeping the elapsed time. Does nothing if the stopwatch is stopped.
+    pub fn stop(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.accumulated += Instant::now().saturating_duration_since(started);
+        }
+    }
+
+    /// Returns true if the stopwatch is running.
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Total time the stopwatch has been running since it was created or reset.
+    pub fn elapsed(&self) -> Duration {
+        match self.started {
+            Some(started) => self.accumulated + Instant::now().saturating_duration_since(started),
+            None => self.accumulated,
+        }
+    }
+
+    /// Returns the running time since the previous lap, and starts a new lap.
+    ///
+    /// The first lap starts when the stopwatch is created or reset. Time while the stopwatch is
+    /// stopped doesn't count towards the lap.
+    pub fn lap(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        let lap = elapsed - self.lap;
+        self.lap = elapsed;
+        lap
+    }
+
+    /// Stop the stopwatch and clear the elapsed time and laps, as if it was just created.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(all(test, feature = "mock-driver"))]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::MockDriver;
+
+    #[test]
+    #[serial]
+    fn test_stopwatch() {
+        let driver = MockDriver::get();
+        driver.reset();
+        driver.advance(Duration::from_secs(1));
+
+        let mut sw = Stopwatch::new();
+        driver.advance(Duration::from_millis(5));
+        assert_eq!(sw.elapsed(), Duration::ZERO);
+
+        sw.start();
+        assert!(sw.is_running());
+        driver.advance(Duration::from_millis(10));
+        assert_eq!(sw.elapsed(), Duration::from_millis(10));
+        assert_eq!(sw.lap(), Duration::from_millis(10));
+        driver.advance(Duration::from_millis(25));
+        assert_eq!(sw.lap(), Duration::from_millis(25));
+        assert_eq!(sw.lap(), Duration::ZERO);
+        assert_eq!(sw.elapsed(), Duration::from_millis(35));
+
+        sw.reset();
+        assert!(!sw.is_running());
+        driver.advance(Duration::from_millis(5));
+        assert_eq!(sw.elapsed(), Duration::ZERO);
+        assert_eq!(sw.lap(), Duration::ZERO);
+    }
+
+    #[test]
+    #[serial]
+    fn test_stopwatch_resume() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let mut sw = Stopwatch::new();
+        sw.start();
+        driver.advance(Duration::from_millis(10));
+        sw.stop();
+        assert!(!sw.is_running());
+
+        // Time while stopped is not counted, in the total or the lap.
+        driver.advance(Duration::from_millis(100));
+        assert_eq!(sw.elapsed(), Duration::from_millis(10));
+
+        sw.start();
+        sw.start();
+        driver.advance(Duration::from_millis(20));
+        assert_eq!(sw.elapsed(), Duration::from_millis(30));
+        assert_eq!(sw.lap(), Duration::from_millis(30));
+
+        sw.stop();
+        sw.stop();
+        driver.advance(Duration::from_millis(50));
+        sw.start();
+        driver.advance(Duration::from_millis(7));
+        assert_eq!(sw.lap(), Duration::from_millis(7));
+        assert_eq!(sw.elapsed(), Duration::from_millis(37));
+    }
+}
@@ -1,6 +1,6 @@
 use core::future::{poll_fn, Future};
 use core::pin::Pin;
-use core::task::{Context, Poll};
+use core::task::{Context, Poll, Waker};
 
 use futures_core::stream::FusedStream;
 use futures_core::Stream;
@@ -12,10 +12,21 @@ use crate::{Duration, Instant};
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct TimeoutError;
 
+/// Error returned by [`Ticker::next_or_late`] when a tick is observed later than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LateTick {
+    /// How long after its scheduled instant the tick was observed.
+    pub lateness: Duration,
+}
+
 /// Runs a given future with a timeout.
 ///
 /// If the future completes before the timeout, its output is returned. Otherwise, on timeout,
 /// work on the future is stopped (`poll` is no longer called), the future is dropped and `Err(TimeoutError)` is returned.
+///
+/// The future doesn't need to be [`Unpin`]: it is pinned together with the returned future, so
+/// no allocation is needed.
 pub fn with_timeout<F: Future>(timeout: Duration, fut: F) -> TimeoutFuture<F> {
     TimeoutFuture {
         timer: Timer::after(timeout),
@@ -23,6 +34,23 @@ pub fn with_timeout<F: Future>(timeout: Duration, fut: F) -> TimeoutFuture<F> {
     }
 }
 
+/// Runs a given future with a timeout, never waiting longer than `timeout`.
+///
+/// [`with_timeout`] computes its deadline as `Instant::now() + timeout`. As the current tick has
+/// already partially elapsed when `now()` is read, the timeout actually lasts between `timeout`
+/// and `timeout` plus one tick. This variant sets the deadline one tick earlier instead, so it
+/// lasts between `timeout` minus one tick and `timeout`. Use it for hard cutoffs, together with
+/// the `Duration::from_*_floor` constructors if the timeout isn't a whole number of ticks.
+///
+/// Otherwise, this behaves like [`with_timeout`].
+pub fn with_timeout_floor<F: Future>(timeout: Duration, fut: F) -> TimeoutFuture<F> {
+    let ticks = timeout.as_ticks().saturating_sub(1);
+    TimeoutFuture {
+        timer: Timer::after_ticks(ticks),
+        fut,
+    }
+}
+
 /// Runs a given future with a deadline time.
 ///
 /// If the future completes before the deadline, its output is returned. Otherwise, on timeout,
@@ -34,6 +62,44 @@ pub fn with_deadline<F: Future>(at: Instant, fut: F) -> TimeoutFuture<F> {
     }
 }
 
+/// Runs a given future with an optional deadline time.
+///
+/// With `Some` deadline, this behaves like [`with_deadline`]. With `None`, the future is awaited
+/// as is and never times out. This avoids branching in code where a deadline may or may not be set.
+pub fn with_optional_deadline<F: Future>(deadline: Option<Instant>, fut: F) -> OptionalTimeoutFuture<F> {
+    OptionalTimeoutFuture {
+        timer: deadline.map(Timer::at),
+        fut,
+    }
+}
+
+/// Runs a given future with a timeout, returning how much of the timeout was left.
+///
+/// If the future completes before the timeout, its output is returned together with the unused
+/// part of the timeout. This can be passed on as the timeout of a subsequent operation, to share a
+/// single time budget between several steps. If the future completes right at the timeout, it
+/// takes precedence and the remaining time is [`Duration::ZERO`].
+///
+/// Otherwise, on timeout, work on the future is stopped (`poll` is no longer called), the future is
+/// dropped and `Err(TimeoutError)` is returned.
+pub fn with_timeout_remaining<F: Future>(timeout: Duration, fut: F) -> TimeoutRemainingFuture<F> {
+    TimeoutRemainingFuture {
+        timeout: with_timeout(timeout, fut),
+    }
+}
+
+/// Runs a given future with a timeout, returning how long it took to complete.
+///
+/// If the future completes before the timeout, its output is returned together with the time
+/// elapsed since this function was called. Otherwise, on timeout, work on the future is stopped
+/// (`poll` is no longer called), the future is dropped and `Err(TimeoutError)` is returned.
+pub fn with_timeout_timed<F: Future>(timeout: Duration, fut: F) -> TimedTimeoutFuture<F> {
+    TimedTimeoutFuture {
+        start: Instant::now(),
+        timeout: with_timeout(timeout, fut),
+    }
+}
+
 /// Provides functions to run a given future with a timeout or a deadline.
 pub trait WithTimeout: Sized {
     /// Output type of the future.
@@ -45,6 +111,18 @@ pub trait WithTimeout: Sized {
     /// work on the future is stopped (`poll` is no longer called), the future is dropped and `Err(TimeoutError)` is returned.
     fn with_timeout(self, timeout: Duration) -> TimeoutFuture<Self>;
 
+    /// Runs a given future with a timeout, never waiting longer than `timeout`.
+    ///
+    /// See [`with_timeout_floor`] for how this differs from [`with_timeout`](Self::with_timeout).
+    fn with_timeout_floor(self, timeout: Duration) -> TimeoutFuture<Self>;
+
+    /// Runs a given future with a timeout, returning how long it took to complete.
+    ///
+    /// If the future completes before the timeout, its output is returned together with the time
+    /// elapsed since this method was called. Otherwise, on timeout, work on the future is stopped
+    /// (`poll` is no longer called), the future is dropped and `Err(TimeoutError)` is returned.
+    fn with_timeout_timed(self, timeout: Duration) -> TimedTimeoutFuture<Self>;
+
     /// Runs a given future with a deadline time.
     ///
     /// If the future completes before the deadline, its output is returned. Otherwise, on timeout,
@@ -59,6 +137,14 @@ impl<F: Future> WithTimeout for F {
         with_timeout(timeout, self)
     }
 
+    fn with_timeout_floor(self, timeout: Duration) -> TimeoutFuture<Self> {
+        with_timeout_floor(timeout, self)
+    }
+
+    fn with_timeout_timed(self, timeout: Duration) -> TimedTimeoutFuture<Self> {
+        with_timeout_timed(timeout, self)
+    }
+
     fn with_deadline(self, at: Instant) -> TimeoutFuture<Self> {
         with_deadline(at, self)
     }
@@ -92,6 +178,139 @@ impl<F: Future> Future for TimeoutFuture<F> {
     }
 }
 
+/// Future for the [`with_optional_deadline`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OptionalTimeoutFuture<F> {
+    timer: Option<Timer>,
+    fut: F,
+}
+
+impl<F: Unpin> Unpin for OptionalTimeoutFuture<F> {}
+
+impl<F: Future> Future for OptionalTimeoutFuture<F> {
+    type Output = Result<F::Output, TimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let fut = unsafe { Pin::new_unchecked(&mut this.fut) };
+        if let Poll::Ready(x) = fut.poll(cx) {
+            return Poll::Ready(Ok(x));
+        }
+        if let Some(timer) = &mut this.timer {
+            if Pin::new(timer).poll(cx).is_ready() {
+                return Poll::Ready(Err(TimeoutError));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Future for the [`with_timeout_timed`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimedTimeoutFuture<F> {
+    start: Instant,
+    timeout: TimeoutFuture<F>,
+}
+
+impl<F: Unpin> Unpin for TimedTimeoutFuture<F> {}
+
+impl<F: Future> Future for TimedTimeoutFuture<F> {
+    type Output = Result<(F::Output, Duration), TimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let start = this.start;
+        let timeout = unsafe { Pin::new_unchecked(&mut this.timeout) };
+        timeout.poll(cx).map_ok(|x| (x, start.elapsed()))
+    }
+}
+
+/// Future for the [`with_timeout_remaining`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeoutRemainingFuture<F> {
+    timeout: TimeoutFuture<F>,
+}
+
+impl<F: Unpin> Unpin for TimeoutRemainingFuture<F> {}
+
+impl<F: Future> Future for TimeoutRemainingFuture<F> {
+    type Output = Result<(F::Output, Duration), TimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let expires_at = this.timeout.timer.expires_at;
+        let timeout = unsafe { Pin::new_unchecked(&mut this.timeout) };
+        timeout
+            .poll(cx)
+            .map_ok(|x| (x, expires_at.saturating_duration_since(Instant::now())))
+    }
+}
+
+/// A deadline shared by several operations, such as the steps of a protocol handshake.
+///
+/// Each operation is run with [`guard()`](Self::guard), which times it out at the same absolute
+/// instant. Unlike chaining [`with_timeout`] calls with the remaining time, the deadline is
+/// computed once, so no rounding error accumulates between steps.
+///
+/// ``` no_run
+/// use embassy_time::{Deadline, Duration, Instant, TimeoutError, Timer};
+/// # async fn send_hello() {}
+/// # async fn receive_ack() -> u8 { 0 }
+///
+/// # async fn demo() -> Result<u8, TimeoutError> {
+/// let deadline = Deadline::new(Instant::now() + Duration::from_secs(2));
+/// deadline.guard(send_hello()).await?;
+/// let ack = deadline.guard(receive_ack()).await?;
+/// # Ok(ack)
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// Create a deadline at the given instant.
+    pub const fn new(at: Instant) -> Self {
+        Self { at }
+    }
+
+    /// Create a deadline `timeout` from now.
+    pub fn after(timeout: Duration) -> Self {
+        Self::new(Instant::now() + timeout)
+    }
+
+    /// The instant at which the deadline expires.
+    pub const fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Time left until the deadline, or [`Duration::ZERO`] if it has expired.
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has expired.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.at
+    }
+
+    /// Runs a given future, timing it out at the deadline.
+    ///
+    /// This is the same as [`with_deadline`] with the instant of this deadline. A future guarded
+    /// after the deadline has expired times out without waiting, unless it completes right away.
+    pub fn guard<F: Future>(&self, fut: F) -> TimeoutFuture<F> {
+        with_deadline(self.at, fut)
+    }
+}
+
 /// A future that completes at a specified [Instant](struct.Instant.html).
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 #[derive(Debug)]
@@ -111,6 +330,36 @@ impl Timer {
         }
     }
 
+    /// Expire at specified [Instant](struct.Instant.html), resolving to the time at which the timer fired.
+    ///
+    /// The returned instant is read when the timer is polled after its deadline, so comparing
+    /// it to `deadline` gives the wakeup latency. Will expire immediately if the Instant is in the past.
+    pub fn at_returning(deadline: Instant) -> ReturningTimer {
+        ReturningTimer {
+            timer: Self::at(deadline),
+        }
+    }
+
+    /// Expire at the next multiple of `period` since startup.
+    ///
+    /// For example, with a period of one second, this fires on the next whole second of uptime,
+    /// which can be used to line up periodic work across tasks. If the current time is exactly on
+    /// a multiple, this fires at the following one, not immediately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn at_next_multiple_of(period: Duration) -> Self {
+        assert!(period.as_ticks() != 0, "period must not be zero");
+        let period = period.as_ticks();
+        let now = Instant::now().as_ticks();
+        let next = (now / period)
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(period))
+            .unwrap_or(u64::MAX);
+        Self::at(Instant::from_ticks(next))
+    }
+
     /// Expire after specified [Duration](struct.Duration.html).
     /// This can be used as a `sleep` abstraction.
     ///
@@ -144,6 +393,7 @@ impl Timer {
     ///
     /// This method is a convenience wrapper for calling `Timer::after(Duration::from_nanos())`.
     /// For more details, refer to [`Timer::after()`] and [`Duration::from_nanos()`].
+    /// NOTE: Delays this small may be inaccurate, the delay is rounded up to a whole number of ticks.
     #[inline]
     pub fn after_nanos(nanos: u64) -> Self {
         Self::after(Duration::from_nanos(nanos))
@@ -177,6 +427,18 @@ impl Timer {
     }
 }
 
+/// Arms the time driver to wake `waker` at `at`.
+///
+/// With the `critical-section-schedule` feature, this is done in a critical section, so that a
+/// timer armed from an interrupt can't race with the alarm being programmed by thread mode code.
+#[inline]
+fn schedule_wake(at: Instant, waker: &Waker) {
+    #[cfg(feature = "critical-section-schedule")]
+    critical_section::with(|_| embassy_time_driver::schedule_wake(at.as_ticks(), waker));
+    #[cfg(not(feature = "critical-section-schedule"))]
+    embassy_time_driver::schedule_wake(at.as_ticks(), waker);
+}
+
 impl Unpin for Timer {}
 
 impl Future for Timer {
@@ -185,13 +447,66 @@ impl Future for Timer {
         if self.yielded_once && self.expires_at <= Instant::now() {
             Poll::Ready(())
         } else {
-            embassy_time_driver::schedule_wake(self.expires_at.as_ticks(), cx.waker());
+            schedule_wake(self.expires_at, cx.waker());
             self.yielded_once = true;
             Poll::Pending
         }
     }
 }
 
+/// Which of the timers raced by [`race_timers`] fired first, with its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Either {
+    /// The first timer fired first.
+    First(Instant),
+    /// The second timer fired first.
+    Second(Instant),
+}
+
+/// Waits for the earlier of two timers, returning which one fired first.
+///
+/// If both timers expire at the same instant, the first one wins. The other timer is dropped.
+///
+/// ``` no_run
+/// use embassy_time::{race_timers, Either, Timer};
+///
+/// # async fn example() {
+/// match race_timers(Timer::after_millis(10), Timer::after_secs(1)).await {
+///     Either::First(_) => { /* retry */ }
+///     Either::Second(_) => { /* give up */ }
+/// }
+/// # }
+/// ```
+pub fn race_timers(a: Timer, b: Timer) -> impl Future<Output = Either> + Send + Sync {
+    let (winner, timer) = if a.expires_at <= b.expires_at {
+        (Either::First(a.expires_at), a)
+    } else {
+        (Either::Second(b.expires_at), b)
+    };
+    async move {
+        timer.await;
+        winner
+    }
+}
+
+/// A [`Timer`] that resolves to the [`Instant`] at which it fired, see [`Timer::at_returning()`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReturningTimer {
+    timer: Timer,
+}
+
+impl Unpin for ReturningTimer {}
+
+impl Future for ReturningTimer {
+    type Output = Instant;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.timer).poll(cx).map(|()| Instant::now())
+    }
+}
+
 /// Asynchronous stream that yields every Duration, indefinitely.
 ///
 /// This stream will tick at uniform intervals, even if blocking work is performed between ticks.
@@ -229,6 +544,14 @@ impl Future for Timer {
 /// }
 /// ```
 ///
+/// This is a fixed-rate ticker: ticks are scheduled from the previous scheduled tick, regardless
+/// of how long the task took to process it. To wait a fixed delay between the end of the processing
+/// and the next tick instead, like the `Timer` loop above, use [`Ticker::every_fixed_delay`].
+///
+/// `Ticker` also implements [`Stream`] (and [`FusedStream`]), yielding `()` on each tick, so it
+/// can be used with stream combinators such as the ones of `futures::StreamExt`. This doesn't need
+/// any feature or allocation.
+///
 /// ## Cancel safety
 /// It is safe to cancel waiting for the next tick,
 /// meaning no tick is lost if the Future is dropped.
@@ -237,31 +560,152 @@ impl Future for Timer {
 pub struct Ticker {
     expires_at: Instant,
     duration: Duration,
+    /// Whether `expires_at` is valid, see [`Ticker::every_lazy`].
+    started: bool,
+    /// Whether the ticker restarts after each tick, see [`Ticker::every_fixed_delay`].
+    fixed_delay: bool,
 }
 
 impl Ticker {
     /// Creates a new ticker that ticks at the specified duration interval.
     pub fn every(duration: Duration) -> Self {
         let expires_at = Instant::now() + duration;
-        Self { expires_at, duration }
+        Self {
+            expires_at,
+            duration,
+            started: true,
+            fixed_delay: false,
+        }
+    }
+
+    /// Creates a new ticker that ticks with a fixed delay of `duration` between ticks.
+    ///
+    /// The first tick fires `duration` after the ticker is created. After that, each tick fires
+    /// `duration` after the ticker is next polled, that is after the task is done processing the
+    /// previous tick and waits for the next one. The period therefore stretches by the processing
+    /// time, and ticks never fire back-to-back when the task falls behind.
+    ///
+    /// ``` no_run
+    /// use embassy_time::{Duration, Ticker};
+    /// # async fn poll_sensor() {}
+    ///
+    /// #[embassy_executor::task]
+    /// async fn sensor_task() {
+    ///     // Leave the bus idle for at least 10ms between readings, however long they take.
+    ///     let mut ticker = Ticker::every_fixed_delay(Duration::from_millis(10));
+    ///     loop {
+    ///         ticker.next().await;
+    ///         poll_sensor().await;
+    ///     }
+    /// }
+    /// ```
+    pub fn every_fixed_delay(duration: Duration) -> Self {
+        Self {
+            fixed_delay: true,
+            ..Self::every(duration)
+        }
+    }
+
+    /// Creates a new ticker that ticks at the specified duration interval, starting lazily.
+    ///
+    /// Unlike [`every()`](Self::every), this doesn't read the current time, so it can be used in
+    /// `const` and `static` initializers. The ticker starts when it's first polled: the first tick
+    /// fires one period after that, instead of one period after the ticker was created.
+    ///
+    /// ``` no_run
+    /// use embassy_time::{Duration, Ticker};
+    ///
+    /// const BLINK: Ticker = Ticker::every_lazy(Duration::from_millis(500));
+    /// ```
+    pub const fn every_lazy(duration: Duration) -> Self {
+        Self {
+            expires_at: Instant::MIN,
+            duration,
+            started: false,
+            fixed_delay: false,
+        }
+    }
+
+    /// Returns the period of the ticker.
+    pub const fn period(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns how long until the next tick, or zero if it's already due.
+    ///
+    /// This doesn't consume the tick. For a ticker created with [`every_lazy()`](Self::every_lazy)
+    /// that hasn't started yet, or a [fixed-delay](Self::every_fixed_delay) ticker that isn't
+    /// waiting for a tick, this is the full period.
+    pub fn time_until_next(&self) -> Duration {
+        match self.started {
+            true => self.expires_at.saturating_duration_since(Instant::now()),
+            false => self.duration,
+        }
     }
 
     /// Resets the ticker back to its original state.
     /// This causes the ticker to go back to zero, even if the current tick isn't over yet.
     pub fn reset(&mut self) {
         self.expires_at = Instant::now() + self.duration;
+        self.started = true;
+    }
+
+    /// Starts a ticker created with [`every_lazy()`](Self::every_lazy), if it isn't yet.
+    fn start(&mut self) {
+        if !self.started {
+            self.reset();
+        }
+    }
+
+    /// Consumes the due tick, scheduling the next one `after` it.
+    fn consume(&mut self, after: Duration) {
+        self.expires_at += after;
+        // A fixed-delay ticker restarts when it's next polled instead.
+        self.started = !self.fixed_delay;
     }
 
     /// Reset the ticker at the deadline.
-    /// If the deadline is in the past, the ticker will fire instantly.
+    ///
+    /// The ticker behaves as if it had been reset exactly at `deadline`: the next tick
+    /// fires at `deadline + period`, and subsequent ticks keep the configured cadence from there.
+    /// This can be used to phase-align a ticker to an absolute point in time, such as an external
+    /// synchronization edge.
+    ///
+    /// If the resulting tick is in the past, the ticker will fire instantly, and then
+    /// resume its cadence relative to `deadline`.
     pub fn reset_at(&mut self, deadline: Instant) {
         self.expires_at = deadline + self.duration;
+        self.started = true;
     }
 
     /// Resets the ticker, after the specified duration has passed.
     /// If the specified duration is zero, the next tick will be after the duration of the ticker.
     pub fn reset_after(&mut self, after: Duration) {
         self.expires_at = Instant::now() + after + self.duration;
+        self.started = true;
+    }
+
+    /// Changes the period of the ticker.
+    ///
+    /// The already scheduled tick is kept as is, so the change takes effect from the
+    /// interval following it onwards. This preserves the phase of the ticker, unlike
+    /// creating a new one.
+    pub fn set_period(&mut self, period: Duration) {
+        self.duration = period;
+    }
+
+    /// Drops the next tick, without waiting for it.
+    ///
+    /// The next expiration is pushed back by one period, so the following [`next()`](Self::next)
+    /// resolves one period later than it would have. This can be used to avoid firing back-to-back
+    /// when the task fell behind. The ticker keeps its phase.
+    ///
+    /// Skipping is relative to the current schedule: a later [`reset()`](Self::reset) (or one of
+    /// its variants) reschedules the ticker from scratch, undoing any skipped ticks.
+    pub fn skip(&mut self) {
+        self.start();
+        let dur = self.duration;
+        self.expires_at += dur;
     }
 
     /// Waits for the next tick.
@@ -270,12 +714,69 @@ impl Ticker {
     /// The produced Future is cancel safe, meaning no tick is lost if the Future is dropped.
     pub fn next(&mut self) -> impl Future<Output = ()> + Send + Sync + '_ {
         poll_fn(|cx| {
+            self.start();
             if self.expires_at <= Instant::now() {
                 let dur = self.duration;
-                self.expires_at += dur;
+                self.consume(dur);
                 Poll::Ready(())
             } else {
-                embassy_time_driver::schedule_wake(self.expires_at.as_ticks(), cx.waker());
+                schedule_wake(self.expires_at, cx.waker());
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Waits for the next tick, failing if it is observed more than `slack` after it was scheduled.
+    ///
+    /// This behaves like [`next()`](Self::next), but also checks how late the task got to run:
+    /// if the tick was due more than `slack` before the current time, [`LateTick`] is returned
+    /// with the lateness. This can be used to detect that the executor is overloaded. The tick
+    /// is consumed either way, so the ticker keeps its cadence.
+    ///
+    /// ## Cancel safety
+    /// The produced Future is cancel safe, meaning no tick is lost if the Future is dropped.
+    pub fn next_or_late(&mut self, slack: Duration) -> impl Future<Output = Result<(), LateTick>> + Send + Sync + '_ {
+        poll_fn(move |cx| {
+            self.start();
+            let now = Instant::now();
+            if self.expires_at <= now {
+                let lateness = now - self.expires_at;
+                let dur = self.duration;
+                self.consume(dur);
+                match lateness > slack {
+                    true => Poll::Ready(Err(LateTick { lateness })),
+                    false => Poll::Ready(Ok(())),
+                }
+            } else {
+                schedule_wake(self.expires_at, cx.waker());
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Waits for the next tick, returning how many whole periods were missed.
+    ///
+    /// Unlike [`next()`](Self::next), which fires back-to-back until it has caught up when
+    /// the task falls behind, this skips all ticks that are already overdue and returns their count.
+    /// The ticker keeps its phase: the following tick is scheduled at the next period boundary
+    /// after the current time. A return value of `0` means the tick was on time.
+    ///
+    /// ## Cancel safety
+    /// The produced Future is cancel safe, meaning no tick is lost if the Future is dropped.
+    pub fn next_with_missed(&mut self) -> impl Future<Output = u32> + Send + Sync + '_ {
+        poll_fn(|cx| {
+            self.start();
+            let now = Instant::now();
+            if self.expires_at <= now {
+                let period = self.duration.as_ticks();
+                let missed = match period {
+                    0 => 0,
+                    _ => (now.as_ticks() - self.expires_at.as_ticks()) / period,
+                };
+                self.consume(Duration::from_ticks(period * (missed + 1)));
+                Poll::Ready(missed.try_into().unwrap_or(u32::MAX))
+            } else {
+                schedule_wake(self.expires_at, cx.waker());
                 Poll::Pending
             }
         })
@@ -287,12 +788,13 @@ impl Unpin for Ticker {}
 impl Stream for Ticker {
     type Item = ();
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.start();
         if self.expires_at <= Instant::now() {
             let dur = self.duration;
-            self.expires_at += dur;
+            self.consume(dur);
             Poll::Ready(Some(()))
         } else {
-            embassy_time_driver::schedule_wake(self.expires_at.as_ticks(), cx.waker());
+            schedule_wake(self.expires_at, cx.waker());
             Poll::Pending
         }
     }
@@ -304,3 +806,654 @@ impl FusedStream for Ticker {
         false
     }
 }
+
+/// A [`Ticker`] whose period adapts to the load reported by the caller.
+///
+/// Each call to [`next()`](AdaptiveTicker::next) reports whether the work done since the previous
+/// tick found the system busy. While busy, the period is halved, down to `min`, so that the ticker
+/// quickly becomes responsive. While idle, the period grows by `step`, up to `max`, to save power.
+/// The new period applies to the interval that is currently in progress.
+///
+/// The ticker starts out at `max`.
+///
+/// ``` no_run
+/// use embassy_time::{AdaptiveTicker, Duration};
+/// # fn poll_device() -> bool { false }
+///
+/// #[embassy_executor::task]
+/// async fn poller() {
+///     let mut ticker = AdaptiveTicker::new(Duration::from_millis(1), Duration::from_millis(100), Duration::from_millis(10));
+///     loop {
+///         let busy = poll_device();
+///         ticker.next(busy).await;
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AdaptiveTicker {
+    ticker: Ticker,
+    min: Duration,
+    max: Duration,
+    step: Duration,
+}
+
+impl AdaptiveTicker {
+    /// Creates a new adaptive ticker with a period within `[min, max]`, growing by `step` when idle.
+    ///
+    /// # Panics
+    /// Panics if `min` is greater than `max`.
+    pub fn new(min: Duration, max: Duration, step: Duration) -> Self {
+        assert!(min <= max, "min must not be greater than max");
+        Self {
+            ticker: Ticker::every(max),
+            min,
+            max,
+            step,
+        }
+    }
+
+    /// The current period of the ticker.
+    pub fn period(&self) -> Duration {
+        self.ticker.period()
+    }
+
+    /// Adapts the period to the reported load, then waits for the next tick.
+    ///
+    /// ## Cancel safety
+    /// The produced Future is cancel safe, meaning no tick is lost if the Future is dropped.
+    /// However, the period is adapted every time this method is called.
+    pub fn next(&mut self, busy: bool) -> impl Future<Output = ()> + Send + Sync + '_ {
+        let old = self.ticker.duration;
+        let new = if busy {
+            (old / 2).max(self.min)
+        } else {
+            old.checked_add(self.step).unwrap_or(Duration::MAX).min(self.max)
+        };
+        self.ticker.expires_at = self.ticker.expires_at - old + new;
+        self.ticker.duration = new;
+        self.ticker.next()
+    }
+}
+
+#[cfg(all(test, feature = "mock-driver"))]
+mod tests {
+    use core::future::{pending, Future};
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::MockDriver;
+
+    fn setup() -> &'static MockDriver {
+        let driver = MockDriver::get();
+        driver.reset();
+        driver
+    }
+
+    fn poll_once<F: Future>(fut: F) -> Poll<F::Output> {
+        let fut = pin!(fut);
+        fut.poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    #[serial]
+    fn test_timer_at_returning() {
+        let driver = setup();
+        let mut timer = pin!(Timer::at_returning(Instant::from_millis(100)));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Pending);
+
+        // Polled late, the timer reports when it actually fired.
+        driver.advance(Duration::from_millis(130));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Ready(Instant::from_millis(130)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_race_timers() {
+        let driver = setup();
+        for (a, b, winner) in [
+            (10, 20, Either::First(Instant::from_millis(10))),
+            (20, 10, Either::Second(Instant::from_millis(10))),
+            (10, 10, Either::First(Instant::from_millis(10))),
+        ] {
+            driver.reset();
+            let mut race = pin!(race_timers(Timer::after_millis(a), Timer::after_millis(b)));
+            assert_eq!(poll_once(race.as_mut()), Poll::Pending);
+            driver.advance(Duration::from_millis(9));
+            assert_eq!(poll_once(race.as_mut()), Poll::Pending);
+            driver.advance(Duration::from_millis(1));
+            assert_eq!(poll_once(race.as_mut()), Poll::Ready(winner));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_timer_after_nanos() {
+        let driver = setup();
+        // 2.5us is rounded up to 3 ticks at 1MHz.
+        let mut timer = pin!(Timer::after_nanos(2500));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Pending);
+        assert_eq!(driver.next_alarm(), Some(Instant::from_ticks(3)));
+
+        driver.advance(Duration::from_ticks(2));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Pending);
+        driver.advance(Duration::from_ticks(1));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Ready(()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_timer_at_next_multiple_of() {
+        let driver = setup();
+        let period = Duration::from_millis(100);
+
+        // Off a boundary, fires at the next one.
+        driver.advance(Duration::from_millis(250));
+        let mut timer = pin!(Timer::at_next_multiple_of(period));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Pending);
+        assert_eq!(driver.next_alarm(), Some(Instant::from_millis(300)));
+        driver.advance(Duration::from_millis(49));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Pending);
+        driver.advance(Duration::from_millis(1));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Ready(()));
+
+        // On a boundary, fires at the following one, not immediately.
+        let mut timer = pin!(Timer::at_next_multiple_of(period));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Pending);
+        assert_eq!(driver.next_alarm(), Some(Instant::from_millis(400)));
+        driver.advance(Duration::from_millis(100));
+        assert_eq!(poll_once(timer.as_mut()), Poll::Ready(()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_reset_at() {
+        let driver = setup();
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        ticker.reset_at(Instant::from_millis(2500));
+        driver.advance(Duration::from_millis(3000));
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(500));
+        assert!(poll_once(ticker.next()).is_ready());
+        driver.advance(Duration::from_millis(999));
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(1));
+        assert!(poll_once(ticker.next()).is_ready());
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_reset_at_past() {
+        let driver = setup();
+        driver.advance(Duration::from_secs(10));
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        // Both the 2s and 3s ticks are already due, the 4s one is not.
+        ticker.reset_at(Instant::from_secs(1));
+        assert!(poll_once(ticker.next()).is_ready());
+        assert!(poll_once(ticker.next()).is_ready());
+        assert_eq!(ticker.expires_at, Instant::from_secs(4));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_every_lazy() {
+        const TICKER: Ticker = Ticker::every_lazy(Duration::from_millis(100));
+
+        let driver = setup();
+        let mut ticker = TICKER;
+        assert_eq!(ticker.period(), Duration::from_millis(100));
+
+        // The ticker starts when first polled, not when created.
+        driver.advance(Duration::from_millis(50));
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(99));
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(1));
+        assert!(poll_once(ticker.next()).is_ready());
+        assert_eq!(Instant::now(), Instant::from_millis(150));
+
+        driver.advance(Duration::from_millis(99));
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(1));
+        assert!(poll_once(ticker.next()).is_ready());
+        assert_eq!(ticker.expires_at, Instant::from_millis(350));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_next_with_missed() {
+        let driver = setup();
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        driver.advance(Duration::from_secs(1));
+        assert_eq!(poll_once(ticker.next_with_missed()), Poll::Ready(0));
+
+        // Block past three more periods: the 2s tick fires, the 3s and 4s ticks are missed.
+        driver.advance(Duration::from_millis(3500));
+        assert_eq!(poll_once(ticker.next_with_missed()), Poll::Ready(2));
+        assert_eq!(poll_once(ticker.next_with_missed()), Poll::Pending);
+
+        // The cadence is preserved.
+        driver.advance(Duration::from_millis(500));
+        assert_eq!(poll_once(ticker.next_with_missed()), Poll::Ready(0));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_next_or_late() {
+        let driver = setup();
+        let slack = Duration::from_millis(100);
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        // Exactly at the slack boundary is still on time.
+        driver.advance(Duration::from_millis(1100));
+        assert_eq!(poll_once(ticker.next_or_late(slack)), Poll::Ready(Ok(())));
+
+        driver.advance(Duration::from_millis(1001));
+        assert_eq!(
+            poll_once(ticker.next_or_late(slack)),
+            Poll::Ready(Err(LateTick {
+                lateness: Duration::from_millis(101)
+            }))
+        );
+
+        // The late tick was consumed, the cadence is preserved.
+        assert_eq!(poll_once(ticker.next_or_late(slack)), Poll::Pending);
+        driver.advance(Duration::from_millis(899));
+        assert_eq!(poll_once(ticker.next_or_late(slack)), Poll::Ready(Ok(())));
+        assert_eq!(ticker.expires_at, Instant::from_secs(4));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_skip() {
+        let driver = setup();
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        // Block past the 1s tick, and drop it instead of firing late.
+        driver.advance(Duration::from_millis(1500));
+        ticker.skip();
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(499));
+        assert!(poll_once(ticker.next()).is_pending());
+        driver.advance(Duration::from_millis(1));
+        assert!(poll_once(ticker.next()).is_ready());
+        assert_eq!(ticker.expires_at, Instant::from_secs(3));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_time_until_next() {
+        let driver = setup();
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+        assert_eq!(ticker.time_until_next(), Duration::from_secs(1));
+
+        driver.advance(Duration::from_millis(300));
+        assert_eq!(ticker.time_until_next(), Duration::from_millis(700));
+
+        // Zero once due, and the tick is still there.
+        driver.advance(Duration::from_millis(900));
+        assert_eq!(ticker.time_until_next(), Duration::ZERO);
+        assert!(poll_once(ticker.next()).is_ready());
+        assert_eq!(ticker.time_until_next(), Duration::from_millis(800));
+
+        let lazy = Ticker::every_lazy(Duration::from_millis(100));
+        assert_eq!(lazy.time_until_next(), Duration::from_millis(100));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_stream() {
+        let driver = setup();
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        let mut ticks = Vec::new();
+        while ticks.len() < 3 {
+            match poll_once(poll_fn(|cx| Pin::new(&mut ticker).poll_next(cx))) {
+                Poll::Ready(tick) => ticks.push((Instant::now(), tick)),
+                Poll::Pending => driver.advance(Duration::from_millis(500)),
+            }
+        }
+        assert_eq!(
+            ticks,
+            [
+                (Instant::from_secs(1), Some(())),
+                (Instant::from_secs(2), Some(())),
+                (Instant::from_secs(3), Some(()))
+            ]
+        );
+        assert!(!ticker.is_terminated());
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_fixed_delay() {
+        // Ticks 4 times, simulating 30ms of processing after each tick.
+        fn tick_times(driver: &MockDriver, mut ticker: Ticker) -> Vec<u64> {
+            driver.block_on(async {
+                let mut ticks = Vec::new();
+                for _ in 0..4 {
+                    ticker.next().await;
+                    ticks.push(Instant::now().as_millis());
+                    driver.advance(Duration::from_millis(30));
+                }
+                ticks
+            })
+        }
+
+        let driver = setup();
+        driver.set_auto_advance(true);
+
+        // Fixed rate keeps the cadence, fixed delay drifts by the processing time.
+        let fixed_rate = tick_times(driver, Ticker::every(Duration::from_millis(100)));
+        assert_eq!(fixed_rate, [100, 200, 300, 400]);
+
+        driver.reset();
+        driver.set_auto_advance(true);
+        let fixed_delay = tick_times(driver, Ticker::every_fixed_delay(Duration::from_millis(100)));
+        assert_eq!(fixed_delay, [100, 230, 360, 490]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_fixed_delay_late() {
+        let driver = setup();
+        let mut ticker = Ticker::every_fixed_delay(Duration::from_millis(100));
+
+        // Once late, a fixed-delay ticker doesn't try to catch up.
+        driver.advance(Duration::from_millis(350));
+        assert_eq!(poll_once(ticker.next()), Poll::Ready(()));
+        assert_eq!(ticker.time_until_next(), Duration::from_millis(100));
+        driver.advance(Duration::from_millis(20));
+        assert_eq!(poll_once(ticker.next()), Poll::Pending);
+        assert_eq!(ticker.time_until_next(), Duration::from_millis(100));
+        driver.advance(Duration::from_millis(100));
+        assert_eq!(poll_once(ticker.next()), Poll::Ready(()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ticker_set_period() {
+        let driver = setup();
+        let mut ticker = Ticker::every(Duration::from_secs(1));
+
+        // The in-flight interval is unaffected.
+        ticker.set_period(Duration::from_secs(2));
+        driver.advance(Duration::from_millis(999));
+        assert_eq!(poll_once(ticker.next()), Poll::Pending);
+        driver.advance(Duration::from_millis(1));
+        assert_eq!(poll_once(ticker.next()), Poll::Ready(()));
+
+        // The following intervals use the new period.
+        driver.advance(Duration::from_secs(1));
+        assert_eq!(poll_once(ticker.next()), Poll::Pending);
+        driver.advance(Duration::from_secs(1));
+        assert_eq!(poll_once(ticker.next()), Poll::Ready(()));
+        assert_eq!(ticker.expires_at, Instant::from_secs(5));
+    }
+
+    #[test]
+    #[serial]
+    fn test_adaptive_ticker() {
+        let driver = setup();
+        let min = Duration::from_millis(10);
+        let max = Duration::from_millis(80);
+        let mut ticker = AdaptiveTicker::new(min, max, Duration::from_millis(20));
+        assert_eq!(ticker.period(), max);
+
+        // Sustained load shrinks the period down to `min`.
+        for expected in [40, 20, 10, 10] {
+            {
+                let mut fut = pin!(ticker.next(true));
+                assert_eq!(poll_once(fut.as_mut()), Poll::Pending);
+                driver.advance(Duration::from_millis(expected));
+                assert_eq!(poll_once(fut.as_mut()), Poll::Ready(()));
+            }
+            assert_eq!(ticker.period(), Duration::from_millis(expected));
+        }
+
+        // Sustained idle grows the period back up to `max`.
+        for expected in [30, 50, 70, 80, 80] {
+            {
+                let mut fut = pin!(ticker.next(false));
+                driver.advance(Duration::from_millis(expected - 1));
+                assert_eq!(poll_once(fut.as_mut()), Poll::Pending);
+                driver.advance(Duration::from_millis(1));
+                assert_eq!(poll_once(fut.as_mut()), Poll::Ready(()));
+            }
+            assert_eq!(ticker.period(), Duration::from_millis(expected));
+        }
+    }
+
+    #[cfg(feature = "critical-section-schedule")]
+    #[test]
+    #[serial]
+    fn test_concurrent_arming() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::task::Wake;
+
+        struct CountingWaker(AtomicUsize);
+
+        impl Wake for CountingWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let driver = setup();
+        let wakers = [0, 1].map(|_| Arc::new(CountingWaker(AtomicUsize::new(0))));
+
+        // Two contexts arming timers at the same time, like thread mode code and an interrupt handler.
+        std::thread::scope(|s| {
+            for (i, counter) in wakers.iter().enumerate() {
+                let waker = Waker::from(counter.clone());
+                s.spawn(move || {
+                    for n in 0..100 {
+                        let mut timer = pin!(Timer::after_millis(10 + (i as u64 * 100 + n) % 20));
+                        assert!(timer.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+                    }
+                });
+            }
+        });
+
+        for counter in &wakers {
+            assert_eq!(counter.0.load(Ordering::Relaxed), 0);
+        }
+        driver.advance(Duration::from_millis(30));
+        for counter in &wakers {
+            assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        }
+        assert_eq!(driver.next_alarm(), None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_with_timeout_remaining() {
+        use core::cell::Cell;
+
+        let driver = setup();
+        let done = Cell::new(false);
+        let mut cx = Context::from_waker(Waker::noop());
+        let op = || poll_fn(|_| if done.get() { Poll::Ready(7) } else { Poll::Pending });
+
+        // Completes early.
+        let mut fut = pin!(with_timeout_remaining(Duration::from_millis(100), op()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(30));
+        done.set(true);
+        assert_eq!(
+            fut.as_mut().poll(&mut cx),
+            Poll::Ready(Ok((7, Duration::from_millis(70))))
+        );
+
+        // Completes in the same poll as the timeout fires.
+        done.set(false);
+        let mut fut = pin!(with_timeout_remaining(Duration::from_millis(100), op()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(100));
+        done.set(true);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok((7, Duration::ZERO))));
+
+        // Times out.
+        done.set(false);
+        let mut fut = pin!(with_timeout_remaining(Duration::from_millis(100), op()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(100));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(TimeoutError)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_deadline() {
+        use core::cell::Cell;
+
+        let driver = setup();
+        let done = Cell::new(false);
+        let mut cx = Context::from_waker(Waker::noop());
+        let op = || poll_fn(|_| if done.get() { Poll::Ready(7) } else { Poll::Pending });
+
+        let deadline = Deadline::after(Duration::from_millis(100));
+        assert_eq!(deadline.at(), Instant::from_millis(100));
+
+        // The first step completes in time.
+        let mut fut = pin!(deadline.guard(op()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(60));
+        done.set(true);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(7)));
+        assert_eq!(deadline.remaining(), Duration::from_millis(40));
+
+        // The second step times out at the same deadline, not 100ms after it started.
+        done.set(false);
+        let mut fut = pin!(deadline.guard(op()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(39));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        assert!(!deadline.is_expired());
+        driver.advance(Duration::from_millis(1));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(TimeoutError)));
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+
+        // Later steps time out without waiting.
+        let mut fut = pin!(deadline.guard(pending::<()>()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(TimeoutError)));
+        assert_eq!(driver.now_instant(), Instant::from_millis(100));
+    }
+
+    #[test]
+    #[serial]
+    fn test_with_timeout_not_unpin() {
+        use core::marker::PhantomPinned;
+
+        /// Records its own address on the first poll, and checks that it didn't move since.
+        struct SelfReferential {
+            this: *const SelfReferential,
+            polls: u32,
+            _pinned: PhantomPinned,
+        }
+
+        impl Future for SelfReferential {
+            type Output = u32;
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+                let this = unsafe { self.get_unchecked_mut() };
+                if this.this.is_null() {
+                    this.this = this;
+                }
+                assert_eq!(this.this, this as *const _);
+                this.polls += 1;
+                match this.polls {
+                    3 => Poll::Ready(this.polls),
+                    _ => {
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                }
+            }
+        }
+
+        let fut = SelfReferential {
+            this: core::ptr::null(),
+            polls: 0,
+            _pinned: PhantomPinned,
+        };
+        let driver = setup();
+        let mut timeout = pin!(fut.with_timeout(Duration::from_secs(1)));
+        assert_eq!(poll_once(timeout.as_mut()), Poll::Pending);
+        driver.advance(Duration::from_millis(500));
+        assert_eq!(poll_once(timeout.as_mut()), Poll::Pending);
+        assert_eq!(poll_once(timeout.as_mut()), Poll::Ready(Ok(3)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_with_timeout_floor() {
+        let driver = setup();
+        let timeout = Duration::from_millis(10);
+        let mut ceil = pin!(with_timeout(timeout, pending::<()>()));
+        let mut floor = pin!(pending::<()>().with_timeout_floor(timeout));
+        assert_eq!(poll_once(ceil.as_mut()), Poll::Pending);
+        assert_eq!(poll_once(floor.as_mut()), Poll::Pending);
+
+        // The floor variant fires one tick earlier.
+        driver.advance(timeout - Duration::from_ticks(1));
+        assert_eq!(poll_once(ceil.as_mut()), Poll::Pending);
+        assert_eq!(poll_once(floor.as_mut()), Poll::Ready(Err(TimeoutError)));
+        driver.advance(Duration::from_ticks(1));
+        assert_eq!(poll_once(ceil.as_mut()), Poll::Ready(Err(TimeoutError)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_with_optional_deadline() {
+        let driver = setup();
+        let mut cx = Context::from_waker(Waker::noop());
+        let pending = || poll_fn(|_| Poll::<()>::Pending);
+
+        let mut fut = pin!(with_optional_deadline(Some(Instant::from_millis(50)), pending()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(50));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(TimeoutError)));
+
+        let mut fut = pin!(with_optional_deadline(None, pending()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(driver.next_alarm(), None);
+        driver.advance(Duration::from_secs(1_000_000));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+
+        assert_eq!(poll_once(with_optional_deadline(None, async { 3 })), Poll::Ready(Ok(3)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_with_timeout_timed() {
+        use core::cell::Cell;
+
+        let driver = setup();
+        let done = Cell::new(false);
+        let mut cx = Context::from_waker(Waker::noop());
+        let op = || poll_fn(|_| if done.get() { Poll::Ready(7) } else { Poll::Pending });
+
+        let mut fut = pin!(op().with_timeout_timed(Duration::from_millis(100)));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(42));
+        done.set(true);
+        let Poll::Ready(Ok((7, elapsed))) = fut.as_mut().poll(&mut cx) else {
+            panic!("future should have completed");
+        };
+        assert!(elapsed.as_ticks().abs_diff(Duration::from_millis(42).as_ticks()) <= 1);
+
+        done.set(false);
+        let mut fut = pin!(op().with_timeout_timed(Duration::from_millis(100)));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(100));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(TimeoutError)));
+    }
+}
@@ -0,0 +1,167 @@
+use core::cell::Cell;
+use core::future::Future;
+
+use critical_section::Mutex as CsMutex;
+
+use crate::{Duration, Instant};
+
+/// Records how long it takes for futures to complete, typically lock acquisitions.
+///
+/// This is a small diagnostic helper for finding lock contention. Wrap each `lock()`
+/// call of a mutex with [`WaitStats::measure`], and the time spent waiting is
+/// recorded using [`Instant::now()`]. It works with any mutex that has an async `lock()`,
+/// such as the one in `embassy-sync`.
+///
+/// ``` no_run
+/// use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+/// use embassy_sync::mutex::Mutex;
+/// use embassy_time::WaitStats;
+///
+/// static SHARED: Mutex<CriticalSectionRawMutex, u32> = Mutex::new(0);
+/// static SHARED_STATS: WaitStats = WaitStats::new();
+///
+/// #[embassy_executor::task]
+/// async fn worker() {
+///     let mut guard = SHARED_STATS.measure(SHARED.lock()).await;
+///     *guard += 1;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WaitStats {
+    inner: CsMutex<Cell<Inner>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Inner {
+    count: u32,
+    total: Duration,
+    max: Duration,
+}
+
+impl Inner {
+    const fn new() -> Self {
+        Self {
+            count: 0,
+            total: Duration::from_ticks(0),
+            max: Duration::from_ticks(0),
+        }
+    }
+}
+
+impl WaitStats {
+    /// Create a new `WaitStats` with no recorded waits.
+    pub const fn new() -> Self {
+        Self {
+            inner: CsMutex::new(Cell::new(Inner::new())),
+        }
+    }
+
+    /// Await `fut`, recording how long it took to complete.
+    pub async fn measure<F: Future>(&self, fut: F) -> F::Output {
+        let start = Instant::now();
+        let output = fut.await;
+        self.record(Instant::now().saturating_duration_since(start));
+        output
+    }
+
+    /// Record a single wait of the given duration.
+    pub fn record(&self, wait: Duration) {
+        critical_section::with(|cs| {
+            let cell = self.inner.borrow(cs);
+            let mut inner = cell.get();
+            inner.count = inner.count.saturating_add(1);
+            inner.total = Duration::from_ticks(inner.total.as_ticks().saturating_add(wait.as_ticks()));
+            inner.max = inner.max.max(wait);
+            cell.set(inner);
+        })
+    }
+
+    /// Number of recorded waits.
+    pub fn count(&self) -> u32 {
+        critical_section::with(|cs| self.inner.borrow(cs).get().count)
+    }
+
+    /// Longest recorded wait, or zero if nothing was recorded.
+    pub fn max_wait(&self) -> Duration {
+        critical_section::with(|cs| self.inner.borrow(cs).get().max)
+    }
+
+    /// Average recorded wait, or zero if nothing was recorded.
+    pub fn avg_wait(&self) -> Duration {
+        let inner = critical_section::with(|cs| self.inner.borrow(cs).get());
+        match inner.count {
+            0 => Duration::from_ticks(0),
+            n => inner.total / n,
+        }
+    }
+
+    /// Clear all recorded waits.
+    pub fn reset(&self) {
+        critical_section::with(|cs| self.inner.borrow(cs).set(Inner::new()))
+    }
+}
+
+#[cfg(all(test, feature = "mock-driver"))]
+mod tests {
+    use core::cell::Cell;
+    use core::future::{poll_fn, Future};
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::MockDriver;
+
+    /// A lock that is released manually by the test.
+    struct MockMutex {
+        locked: Cell<bool>,
+    }
+
+    impl MockMutex {
+        fn lock(&self) -> impl Future<Output = ()> + '_ {
+            poll_fn(|_| {
+                if self.locked.replace(true) {
+                    Poll::Pending
+                } else {
+                    Poll::Ready(())
+                }
+            })
+        }
+
+        fn unlock(&self) {
+            self.locked.set(false);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_contended_lock() {
+        let driver = MockDriver::get();
+        driver.reset();
+
+        let stats = WaitStats::new();
+        let mutex = MockMutex {
+            locked: Cell::new(false),
+        };
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // Uncontended.
+        assert!(pin!(stats.measure(mutex.lock())).poll(&mut cx).is_ready());
+
+        // Contended for 30ms.
+        let mut fut = pin!(stats.measure(mutex.lock()));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        driver.advance(Duration::from_millis(30));
+        mutex.unlock();
+        assert!(fut.as_mut().poll(&mut cx).is_ready());
+
+        assert_eq!(stats.count(), 2);
+        assert_eq!(stats.max_wait(), Duration::from_millis(30));
+        assert_eq!(stats.avg_wait(), Duration::from_millis(15));
+
+        stats.reset();
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.avg_wait(), Duration::from_ticks(0));
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "defmt")]
+
+use defmt::export::{fetch_bytes, fetch_string_index};
+use defmt::Format;
+use embassy_time::Instant;
+
+/// Encodes `value` the way `defmt::info!("{}", value)` encodes its argument.
+fn encode<T: Format>(value: &T) -> Vec<u8> {
+    defmt::export::fmt(value);
+    fetch_bytes()
+}
+
+#[test]
+fn instant_is_formatted_as_uptime() {
+    // The string indices are mocked: every interned string gets the next index.
+    let index = fetch_string_index();
+    let bytes = encode(&Instant::from_millis(1_500));
+
+    let mut expected = Vec::new();
+    expected.extend(index.to_le_bytes()); // "{=__internal_FormatSequence}"
+    expected.extend((index + 1).to_le_bytes()); // "{=u64:us}"
+    expected.extend(1_500_000u64.to_le_bytes()); // microseconds, not ticks
+    expected.extend(0u16.to_le_bytes()); // end of the sequence
+    assert_eq!(bytes, expected);
+}